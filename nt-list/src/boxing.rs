@@ -0,0 +1,27 @@
+// Copyright 2026 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use alloc::alloc::{alloc, Layout};
+use alloc::boxed::Box;
+
+/// Moves `element` into a new [`Box`] without panicking or aborting on allocation failure.
+///
+/// Returns the element back as `Err` if no memory could be allocated.
+pub(crate) fn try_box<E>(element: E) -> Result<Box<E>, E> {
+    let layout = Layout::new::<E>();
+    if layout.size() == 0 {
+        // Zero-sized types never allocate.
+        return Ok(Box::new(element));
+    }
+
+    let ptr = unsafe { alloc(layout) }.cast::<E>();
+    if ptr.is_null() {
+        return Err(element);
+    }
+
+    // `Box` uses the global allocator with `Layout::new::<E>()`, which is exactly how we allocated.
+    unsafe {
+        ptr.write(element);
+        Ok(Box::from_raw(ptr))
+    }
+}
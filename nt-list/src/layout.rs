@@ -0,0 +1,199 @@
+// Copyright 2026 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use core::fmt;
+use core::mem;
+
+use crate::private::Sealed;
+use crate::traits::{NtListElement, NtTypedList};
+
+/// Error returned by [`verify_element_layout`] for an invalid [`NtListElement`] implementation.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LayoutError {
+    /// The entry field described by [`NtListElement::offset`] does not fit into the element.
+    OutOfBounds {
+        /// The offset returned by [`NtListElement::offset`].
+        offset: usize,
+        /// The size of the entry field in bytes.
+        entry_size: usize,
+        /// The size of the element in bytes.
+        element_size: usize,
+    },
+    /// The offset returned by [`NtListElement::offset`] is not a multiple of the alignment of
+    /// the entry field.
+    MisalignedOffset {
+        /// The offset returned by [`NtListElement::offset`].
+        offset: usize,
+        /// The alignment of the entry field in bytes.
+        entry_align: usize,
+    },
+    /// The element is less strictly aligned than its entry field.
+    InsufficientAlignment {
+        /// The alignment of the element in bytes.
+        element_align: usize,
+        /// The alignment of the entry field in bytes.
+        entry_align: usize,
+    },
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfBounds {
+                offset,
+                entry_size,
+                element_size,
+            } => write!(
+                f,
+                "entry of {} bytes at offset {} is out of bounds for an element of {} bytes",
+                entry_size, offset, element_size
+            ),
+            Self::MisalignedOffset {
+                offset,
+                entry_align,
+            } => write!(
+                f,
+                "offset {} is not a multiple of the entry alignment of {} bytes",
+                offset, entry_align
+            ),
+            Self::InsufficientAlignment {
+                element_align,
+                entry_align,
+            } => write!(
+                f,
+                "element alignment of {} bytes is less than the entry alignment of {} bytes",
+                element_align, entry_align
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl std::error::Error for LayoutError {}
+
+/// Verifies that the [`NtListElement`] implementation of `E` for the list `L` describes a valid
+/// entry field.
+///
+/// This checks that the entry field given by [`NtListElement::offset`] lies within the element
+/// and is properly aligned.
+/// Derived implementations always pass these checks.
+/// If you have to implement [`NtListElement`] manually, call this function once in your tests to
+/// catch a wrong offset before it leads to memory corruption.
+///
+/// This cannot verify that the offset actually points to an entry field of the correct list.
+pub fn verify_element_layout<E, L>() -> Result<(), LayoutError>
+where
+    E: NtListElement<L>,
+    L: NtTypedList,
+{
+    let offset = E::offset();
+    let entry_layout = L::T::entry_layout::<E, L>();
+    let element_size = mem::size_of::<E>();
+    let element_align = mem::align_of::<E>();
+
+    let fits = element_size
+        .checked_sub(entry_layout.size())
+        .map_or(false, |max_offset| offset <= max_offset);
+    if !fits {
+        return Err(LayoutError::OutOfBounds {
+            offset,
+            entry_size: entry_layout.size(),
+            element_size,
+        });
+    }
+
+    if offset % entry_layout.align() != 0 {
+        return Err(LayoutError::MisalignedOffset {
+            offset,
+            entry_align: entry_layout.align(),
+        });
+    }
+
+    if element_align < entry_layout.align() {
+        return Err(LayoutError::InsufficientAlignment {
+            element_align,
+            entry_align: entry_layout.align(),
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::list::{NtList, NtListEntry};
+    use crate::single_list::{NtSingleList, NtSingleListEntry};
+
+    #[derive(NtList)]
+    enum MyList {}
+
+    #[derive(NtSingleList)]
+    enum MySingleList {}
+
+    #[test]
+    fn test_verify_element_layout() {
+        #[derive(NtListElement)]
+        #[repr(C)]
+        struct MyElement {
+            value: u64,
+            entry: NtListEntry<Self, MyList>,
+            single_entry: NtSingleListEntry<Self, MySingleList>,
+        }
+
+        assert_eq!(verify_element_layout::<MyElement, MyList>(), Ok(()));
+        assert_eq!(verify_element_layout::<MyElement, MySingleList>(), Ok(()));
+    }
+
+    #[test]
+    fn test_verify_element_layout_errors() {
+        #[repr(C)]
+        struct WrongElement {
+            value: u64,
+            entry: NtListEntry<Self, MyList>,
+            single_entry: NtSingleListEntry<Self, MySingleList>,
+        }
+
+        // A hand-written implementation with an offset pointing past the end of the element.
+        unsafe impl NtListElement<MyList> for WrongElement {
+            fn offset() -> usize {
+                mem::size_of::<Self>()
+            }
+        }
+
+        // A hand-written implementation with an offset into the middle of `value`.
+        unsafe impl NtListElement<MySingleList> for WrongElement {
+            fn offset() -> usize {
+                1
+            }
+        }
+
+        let size = mem::size_of::<WrongElement>();
+        let entry_size = mem::size_of::<NtListEntry<WrongElement, MyList>>();
+        let entry_align = mem::align_of::<NtSingleListEntry<WrongElement, MySingleList>>();
+
+        assert_eq!(
+            verify_element_layout::<WrongElement, MyList>(),
+            Err(LayoutError::OutOfBounds {
+                offset: size,
+                entry_size,
+                element_size: size,
+            })
+        );
+        assert_eq!(
+            verify_element_layout::<WrongElement, MySingleList>(),
+            Err(LayoutError::MisalignedOffset {
+                offset: 1,
+                entry_align,
+            })
+        );
+
+        let element = WrongElement {
+            value: 0,
+            entry: NtListEntry::new(),
+            single_entry: NtSingleListEntry::new(),
+        };
+        let _ = (element.value, element.entry, element.single_entry);
+    }
+}
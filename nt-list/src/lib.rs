@@ -78,6 +78,13 @@
 //! If you want to use the crate in a pure `no_std` environment without heap allocations, include it with
 //! `default-features = false` to disable the default `alloc` feature.
 //!
+//! The optional `std` feature enables functions that depend on the `std` library, like collecting
+//! elements into a `HashSet`.
+//!
+//! The optional `bumpalo` feature adds `ArenaNtBoxingSingleListHead`, which allocates its elements
+//! in a [`bumpalo`] arena instead of individually on the heap.
+//!
+//! [`bumpalo`]: https://crates.io/crates/bumpalo
 //! [`LinkedList`]: alloc::collections::LinkedList
 //! [`LIST_ENTRY`]: https://docs.microsoft.com/en-us/windows/win32/api/ntdef/ns-ntdef-list_entry
 //! [`NtList`]: enum@crate::list::NtList
@@ -93,13 +100,20 @@
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+#[cfg(feature = "std")]
+extern crate std;
+
 // Required for deriving our traits when testing.
 #[cfg(test)]
 extern crate self as nt_list;
 
+#[cfg(feature = "alloc")]
+mod boxing;
+mod layout;
 pub mod list;
 mod private;
 pub mod single_list;
 mod traits;
 
+pub use layout::{verify_element_layout, LayoutError};
 pub use traits::*;
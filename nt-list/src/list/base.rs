@@ -1,14 +1,20 @@
 // Copyright 2022 Colin Finck <colin@reactos.org>
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
+use core::any;
+use core::fmt;
 use core::iter::FusedIterator;
 use core::marker::PhantomPinned;
+use core::mem;
 use core::pin::Pin;
 use core::ptr;
 
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
 use moveit::{new, New};
 
 use super::traits::NtList;
+use crate::single_list::{NtSingleList, NtSingleListEntry, NtSingleListHead};
 use crate::traits::{NtListElement, NtTypedList};
 
 /// A doubly linked list header compatible to [`LIST_ENTRY`] of the Windows NT API.
@@ -37,31 +43,100 @@ where
     E: NtListElement<L>,
     L: NtTypedList<T = NtList>,
 {
+    /// An uninitialized doubly linked list header for `const` and `static` contexts.
+    ///
+    /// The links of a doubly linked list header point to the header itself, so they can only be
+    /// set once the header has reached its final address.
+    /// This constant has null links instead, which lets you place a header in `static` storage,
+    /// e.g. a `static mut` global of a driver, without a heap allocation or `moveit`.
+    ///
+    /// You must call [`init`](Self::init) exactly once before using the header in any other way.
+    ///
+    /// ```
+    /// # use core::pin::Pin;
+    /// # use core::ptr;
+    /// # use nt_list::NtListElement;
+    /// # use nt_list::list::{NtList, NtListEntry, NtListHead};
+    /// #
+    /// # #[derive(NtList)]
+    /// # enum MyList {}
+    /// #
+    /// # #[derive(Default, NtListElement)]
+    /// # #[repr(C)]
+    /// # struct MyElement {
+    /// #     entry: NtListEntry<Self, MyList>,
+    /// #     value: i32,
+    /// # }
+    /// #
+    /// static mut LIST: NtListHead<MyElement, MyList> = NtListHead::EMPTY;
+    ///
+    /// // A static never moves, so it is always pinned.
+    /// let mut list = unsafe { Pin::new_unchecked(&mut *ptr::addr_of_mut!(LIST)) };
+    /// list.as_mut().init();
+    /// assert!(list.as_ref().is_empty());
+    /// ```
+    pub const EMPTY: Self = Self {
+        flink: ptr::null_mut(),
+        blink: ptr::null_mut(),
+        pin: PhantomPinned,
+    };
+
     /// Creates a new doubly linked list.
     ///
     /// This function substitutes [`InitializeListHead`] of the Windows NT API.
     ///
     /// [`InitializeListHead`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-initializelisthead
     pub fn new() -> impl New<Output = Self> {
-        new::of(Self {
-            flink: ptr::null_mut(),
-            blink: ptr::null_mut(),
-            pin: PhantomPinned,
-        })
-        .with(|this| {
+        new::of(Self::EMPTY).with(|this| {
             let this = unsafe { this.get_unchecked_mut() };
             this.flink = (this as *mut Self).cast();
             this.blink = this.flink;
         })
     }
 
+    /// Initializes a doubly linked list header at the given memory location.
+    ///
+    /// Unlike [`new`](Self::new), this doesn't need `moveit` and allows initializing a header
+    /// that is embedded in a larger structure at a stable address, e.g. a device extension.
+    ///
+    /// This function substitutes [`InitializeListHead`] of the Windows NT API.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be valid for writes and properly aligned.
+    /// Any previous value at `ptr` is overwritten without being dropped.
+    /// After this call, the header must never be moved again, just like if it had been pinned.
+    ///
+    /// [`InitializeListHead`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-initializelisthead
+    pub unsafe fn init_at(ptr: *mut Self) {
+        ptr::write(
+            ptr,
+            Self {
+                flink: ptr.cast(),
+                blink: ptr.cast(),
+                pin: PhantomPinned,
+            },
+        );
+    }
+
     /// Moves all elements from `other` to the end of the list.
     ///
     /// This reuses all the nodes from `other` and moves them into `self`.
     /// After this operation, `other` becomes empty.
     ///
     /// This operation computes in *O*(*1*) time.
+    ///
+    /// # Safety
+    ///
+    /// `self` and `other` must be different lists.
+    /// Appending a list to itself would corrupt its links.
+    /// This is checked in debug builds.
     pub unsafe fn append(mut self: Pin<&mut Self>, other: Pin<&mut Self>) {
+        debug_assert!(
+            !ptr::eq(self.as_ref().get_ref(), other.as_ref().get_ref()),
+            "cannot append a list to itself"
+        );
+
         if other.as_ref().is_empty() {
             return;
         }
@@ -80,6 +155,26 @@ where
         other.clear();
     }
 
+    /// Appends an element to the back of the list, checking in debug builds that the element is
+    /// not part of any list.
+    ///
+    /// This is a variant of [`push_back`](Self::push_back) that catches inserting the same element
+    /// twice.
+    /// An element counts as unlinked if both links of its entry are null, which is the state of
+    /// an entry created via [`NtListEntry::new`].
+    /// Elements removed from a list keep their stale links, so their entry needs to be reset
+    /// via [`NtListEntry::new`] before they can be passed to this function.
+    ///
+    /// This function substitutes [`InsertTailList`] of the Windows NT API.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    ///
+    /// [`InsertTailList`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-inserttaillist
+    pub unsafe fn append_element(self: Pin<&mut Self>, element: &mut E) {
+        Self::debug_assert_unlinked(element);
+        self.push_back(element)
+    }
+
     /// Provides a reference to the last element, or `None` if the list is empty.
     ///
     /// This operation computes in *O*(*1*) time.
@@ -87,6 +182,16 @@ where
         (!self.is_empty()).then(|| NtListEntry::containing_record(self.blink))
     }
 
+    /// Returns a pointer to the entry of the last element, or `None` if the list is empty.
+    ///
+    /// This is useful for maintaining an index of entries outside the list.
+    /// The returned pointer is only valid as long as the element is part of the list.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn back_entry(self: Pin<&Self>) -> Option<*const NtListEntry<E, L>> {
+        (!self.is_empty()).then(|| self.blink as *const _)
+    }
+
     /// Provides a mutable reference to the last element, or `None` if the list is empty.
     ///
     /// This operation computes in *O*(*1*) time.
@@ -106,6 +211,103 @@ where
         self_mut.blink = end_marker;
     }
 
+    /// Removes all elements from the list and resets their entries to the unlinked state.
+    ///
+    /// Unlike [`clear`](Self::clear), which leaves stale links in all elements, this lets the
+    /// elements be passed to [`append_element`](Self::append_element) and
+    /// [`prepend_element`](Self::prepend_element) again, just like elements with an entry
+    /// created via [`NtListEntry::new`].
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub unsafe fn clear_and_unlink(mut self: Pin<&mut Self>) {
+        let end_marker = self.as_mut().end_marker_mut();
+        let mut current = self.flink;
+
+        while current != end_marker {
+            let next = (*current).flink;
+            (*current).flink = ptr::null_mut();
+            (*current).blink = ptr::null_mut();
+            current = next;
+        }
+
+        self.clear();
+    }
+
+    /// Fills `out` with pointers to the entries of the list in order and returns how many
+    /// pointers have been written.
+    ///
+    /// If `out` is too small to hold all entries, only the first `out.len()` entries are written
+    /// and the rest of the list is ignored.
+    /// Slots of `out` beyond the returned count are left untouched.
+    ///
+    /// This operation computes in *O*(*min(n, m)*) time, where *m* is the length of `out`.
+    pub unsafe fn collect_entries(self: Pin<&Self>, out: &mut [*const NtListEntry<E, L>]) -> usize {
+        let end_marker = self.end_marker();
+        let mut current = self.flink as *const NtListEntry<E, L>;
+        let mut count = 0;
+
+        for slot in out.iter_mut() {
+            if current == end_marker {
+                break;
+            }
+
+            *slot = current;
+            current = (*current).flink;
+            count += 1;
+        }
+
+        count
+    }
+
+    /// Returns `true` if `entry` is part of this list.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    #[cfg(feature = "alloc")]
+    pub(crate) unsafe fn contains_entry(self: Pin<&Self>, entry: *const NtListEntry<E, L>) -> bool {
+        let end_marker = self.end_marker();
+        let mut current = self.flink as *const NtListEntry<E, L>;
+
+        while current != end_marker {
+            if current == entry {
+                return true;
+            }
+
+            current = (*current).flink;
+        }
+
+        false
+    }
+
+    /// Panics in debug builds if the entry of the given element has any non-null link.
+    fn debug_assert_unlinked(element: &mut E) {
+        let entry = Self::entry(element);
+
+        debug_assert!(
+            unsafe { (*entry).flink.is_null() && (*entry).blink.is_null() },
+            "element is already part of a list"
+        );
+    }
+
+    /// Removes all elements from the list and returns an iterator yielding the entry of each
+    /// removed element in order.
+    ///
+    /// The list is empty right after this call, even if the iterator is not consumed.
+    /// The links of every entry are reset right before it is yielded, so the caller may
+    /// immediately push its element to another list or deallocate it.
+    ///
+    /// This operation computes in *O*(*1*) time, and iterating all entries computes in
+    /// *O*(*n*) time.
+    pub unsafe fn drain_entries(mut self: Pin<&mut Self>) -> DrainEntries<E, L> {
+        let end_marker = self.as_mut().end_marker_mut();
+        let current = self.flink;
+        self.clear();
+
+        DrainEntries {
+            end_marker,
+            current,
+        }
+    }
+
     /// Returns a const pointer to the "end marker element" (which is the address of our own `NtListHead`, but interpreted as a `NtListEntry` element address).
     pub(crate) fn end_marker(self: Pin<&Self>) -> *const NtListEntry<E, L> {
         (self.get_ref() as *const Self).cast()
@@ -118,6 +320,18 @@ where
 
     /// Returns the [`NtListEntry`] for the given element.
     pub(crate) fn entry(element: &mut E) -> *mut NtListEntry<E, L> {
+        // Catch an egregiously wrong `offset` of a hand-written `NtListElement` implementation
+        // before it leads to an out-of-bounds access.
+        debug_assert!(
+            mem::size_of::<E>()
+                .checked_sub(mem::size_of::<NtListEntry<E, L>>())
+                .map_or(false, |max_offset| E::offset() <= max_offset),
+            "NtListElement::offset of {} returns {}, which is out of bounds for an element of {} bytes",
+            any::type_name::<E>(),
+            E::offset(),
+            mem::size_of::<E>()
+        );
+
         let element_ptr = element as *mut E;
 
         // This is the canonical implementation of `byte_add`
@@ -126,6 +340,19 @@ where
         entry.cast()
     }
 
+    /// Returns a pointer to the entry of the given element that is used for this list.
+    ///
+    /// This doesn't check whether `element` is part of any list.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn entry_of(element: &E) -> *const NtListEntry<E, L> {
+        // This is the canonical implementation of `byte_add`
+        (element as *const E)
+            .cast::<u8>()
+            .wrapping_add(E::offset())
+            .cast()
+    }
+
     /// Provides a reference to the first element, or `None` if the list is empty.
     ///
     /// This operation computes in *O*(*1*) time.
@@ -133,6 +360,16 @@ where
         (!self.is_empty()).then(|| NtListEntry::containing_record(self.flink))
     }
 
+    /// Returns a pointer to the entry of the first element, or `None` if the list is empty.
+    ///
+    /// This is useful for maintaining an index of entries outside the list.
+    /// The returned pointer is only valid as long as the element is part of the list.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn front_entry(self: Pin<&Self>) -> Option<*const NtListEntry<E, L>> {
+        (!self.is_empty()).then(|| self.flink as *const _)
+    }
+
     /// Provides a mutable reference to the first element, or `None` if the list is empty.
     ///
     /// This operation computes in *O*(*1*) time.
@@ -140,6 +377,21 @@ where
         (!self.as_ref().is_empty()).then(|| NtListEntry::containing_record_mut(self.flink))
     }
 
+    /// Initializes a header created from [`EMPTY`](Self::EMPTY) at its final address.
+    ///
+    /// This must be called exactly once before the header is used in any other way.
+    /// Calling it on a header that already contains elements detaches all of them without
+    /// touching them, just like [`clear`](Self::clear).
+    ///
+    /// This function substitutes [`InitializeListHead`] of the Windows NT API.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    ///
+    /// [`InitializeListHead`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-initializelisthead
+    pub fn init(self: Pin<&mut Self>) {
+        self.clear()
+    }
+
     /// Returns `true` if the list is empty.
     ///
     /// This function substitutes [`IsListEmpty`] of the Windows NT API.
@@ -169,11 +421,35 @@ where
         IterMut { head, flink, blink }
     }
 
+    /// Returns an iterator yielding references to each element of the list, without requiring
+    /// a pinned reference to the list header.
+    ///
+    /// This is meant for code that already holds a reference to a header at a stable address,
+    /// e.g. one obtained from foreign code, and only wants to read the list.
+    ///
+    /// In addition to the requirements of [`iter`](Self::iter), the list header must not have
+    /// been moved since its elements have been linked to it, and must not be moved while the
+    /// iterator is used.
+    pub unsafe fn iter_raw(&self) -> Iter<'_, E, L> {
+        Pin::new_unchecked(self).iter()
+    }
+
     /// Counts all elements and returns the length of the list.
     ///
+    /// Only the forward links are followed, so no reference to any element is formed.
+    ///
     /// This operation computes in *O*(*n*) time.
     pub unsafe fn len(self: Pin<&Self>) -> usize {
-        self.iter().count()
+        let end_marker = self.end_marker();
+        let mut current = self.flink as *const NtListEntry<E, L>;
+        let mut count = 0;
+
+        while current != end_marker {
+            count += 1;
+            current = (*current).flink;
+        }
+
+        count
     }
 
     /// Removes the last element from the list and returns it, or `None` if the list is empty.
@@ -206,6 +482,23 @@ where
         })
     }
 
+    /// Appends an element to the front of the list, checking in debug builds that the element is
+    /// not part of any list.
+    ///
+    /// This is a variant of [`push_front`](Self::push_front) that catches inserting the same
+    /// element twice.
+    /// See [`append_element`](Self::append_element) for when an element counts as unlinked.
+    ///
+    /// This function substitutes [`InsertHeadList`] of the Windows NT API.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    ///
+    /// [`InsertHeadList`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-insertheadlist
+    pub unsafe fn prepend_element(self: Pin<&mut Self>, element: &mut E) {
+        Self::debug_assert_unlinked(element);
+        self.push_front(element)
+    }
+
     /// Appends an element to the back of the list.
     ///
     /// This function substitutes [`InsertTailList`] of the Windows NT API.
@@ -240,6 +533,30 @@ where
         self.get_unchecked_mut().flink = entry;
     }
 
+    /// Rewrites the backward links of the header and all entries to match the forward links.
+    ///
+    /// This makes a list fully consistent again if only its forward links are known to be
+    /// correct, e.g. after a serialization round-trip that only preserved `flink` pointers.
+    ///
+    /// The forward chain is trusted: starting at the header, following the `flink` pointers
+    /// must visit every element exactly once and eventually lead back to the header.
+    /// A cyclic forward chain that never returns to the header makes this function loop forever.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub unsafe fn repair(mut self: Pin<&mut Self>) {
+        let end_marker = self.as_mut().end_marker_mut();
+        let mut previous = end_marker;
+        let mut current = self.flink;
+
+        while current != end_marker {
+            (*current).blink = previous;
+            previous = current;
+            current = (*current).flink;
+        }
+
+        self.get_unchecked_mut().blink = previous;
+    }
+
     /// Retains only the elements specified by the predicate, passing a mutable reference to it.
     ///
     /// In other words, remove all elements `e` for which `f(&mut e)` returns `false`.
@@ -251,19 +568,161 @@ where
     /// This operation computes in *O*(*n*) time.
     ///
     /// [`RemoveEntryList`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-removeentrylist
-    pub unsafe fn retain<F>(self: Pin<&mut Self>, mut f: F)
+    pub unsafe fn retain<F>(mut self: Pin<&mut Self>, mut f: F)
     where
         F: FnMut(&mut E) -> bool,
     {
-        for element in self.iter_mut() {
+        let end_marker = self.as_mut().end_marker_mut();
+        let mut current = self.flink;
+
+        while current != end_marker {
+            // Store the next pointer before handing out the element and possibly unlinking it.
+            // This doesn't rely on any iterator state, which may be affected by the unlinking.
+            let next = (*current).flink;
+            let element = NtListEntry::containing_record_mut(current);
+
             if !f(element) {
-                let entry = Self::entry(element);
-                (*entry).remove();
+                (*current).remove();
             }
+
+            current = next;
+        }
+    }
+
+    /// Returns a pointer to the entry of the given element that is used for the singly linked
+    /// list `L2`.
+    ///
+    /// This allows code that manages an element in both a doubly and a singly linked list to get
+    /// from the element to its entry of the other list.
+    /// `E` must implement both `NtListElement<L>` and `NtListElement<L2>`, which is the case if
+    /// the derived element structure has an entry field for each of the lists.
+    /// This doesn't check whether `element` is part of any list.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn single_list_entry_of<L2>(element: &E) -> *const NtSingleListEntry<E, L2>
+    where
+        E: NtListElement<L2>,
+        L2: NtTypedList<T = NtSingleList>,
+    {
+        NtSingleListHead::<E, L2>::entry_of(element)
+    }
+
+    /// Moves all elements from index `at` onwards into the empty list `dest`.
+    ///
+    /// The elements before `at` stay in this list.
+    /// If `at` equals the length of the list, `dest` remains empty.
+    /// No element is moved in memory and no element is deallocated, only the links are updated.
+    /// All elements must therefore stay valid for as long as either list uses them.
+    ///
+    /// `dest` must be empty, which is checked in debug builds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at` is greater than the length of the list.
+    ///
+    /// This operation computes in *O*(*at*) time.
+    pub unsafe fn split_off(mut self: Pin<&mut Self>, at: usize, mut dest: Pin<&mut Self>) {
+        debug_assert!(dest.as_ref().is_empty(), "destination list is not empty");
+
+        let end_marker = self.as_mut().end_marker_mut();
+        let mut current = self.flink;
+
+        for i in 0..at {
+            assert!(
+                current != end_marker,
+                "split index {} out of range for list of length {}",
+                at,
+                i
+            );
+            current = (*current).flink;
+        }
+
+        if current == end_marker {
+            return;
         }
+
+        let first_moved = current;
+        let last_moved = self.blink;
+        let last_kept = (*first_moved).blink;
+
+        // Mount the elements from `first_moved` to `last_moved` into `dest`.
+        let dest_end_marker = dest.as_mut().end_marker_mut();
+        (*first_moved).blink = dest_end_marker;
+        (*last_moved).flink = dest_end_marker;
+
+        let dest_mut = dest.get_unchecked_mut();
+        dest_mut.flink = first_moved;
+        dest_mut.blink = last_moved;
+
+        // Make `last_kept` the last element of `self`.
+        // If all elements have been moved, `last_kept` is our own end marker and we become empty.
+        let this = self.get_unchecked_mut();
+        if last_kept == end_marker {
+            this.flink = end_marker;
+        } else {
+            (*last_kept).flink = end_marker;
+        }
+        this.blink = last_kept;
     }
 }
 
+impl<E, L> fmt::Debug for NtListHead<E, L>
+where
+    E: NtListElement<L>,
+    L: NtTypedList<T = NtList>,
+{
+    /// Formats only the forward and backward links of the header.
+    ///
+    /// No element is dereferenced, so this is safe to call even when the elements of the list
+    /// may no longer be valid.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NtListHead")
+            .field("flink", &self.flink)
+            .field("blink", &self.blink)
+            .finish()
+    }
+}
+
+/// Iterator over the entries removed from a doubly linked list.
+///
+/// This iterator is returned from the [`NtListHead::drain_entries`] function.
+pub struct DrainEntries<E: NtListElement<L>, L: NtTypedList<T = NtList>> {
+    end_marker: *mut NtListEntry<E, L>,
+    current: *mut NtListEntry<E, L>,
+}
+
+impl<E, L> Iterator for DrainEntries<E, L>
+where
+    E: NtListElement<L>,
+    L: NtTypedList<T = NtList>,
+{
+    type Item = *mut NtListEntry<E, L>;
+
+    fn next(&mut self) -> Option<*mut NtListEntry<E, L>> {
+        if self.current == self.end_marker {
+            return None;
+        }
+
+        let entry = self.current;
+
+        unsafe {
+            // Advance before resetting the links, because the caller may reuse the entry.
+            self.current = (*entry).flink;
+            (*entry).flink = ptr::null_mut();
+            (*entry).blink = ptr::null_mut();
+        }
+
+        Some(entry)
+    }
+}
+
+impl<E, L> FusedIterator for DrainEntries<E, L>
+where
+    E: NtListElement<L>,
+    L: NtTypedList<T = NtList>,
+{
+}
+
 /// Iterator over the elements of a doubly linked list.
 ///
 /// This iterator is returned from the [`NtListHead::iter`] and [`NtBoxingListHead::iter`] functions.
@@ -286,6 +745,21 @@ where
     }
 }
 
+impl<'a, E, L> Clone for Iter<'a, E, L>
+where
+    E: NtListElement<L>,
+    L: NtTypedList<T = NtList>,
+{
+    /// Returns an independent iterator at the same position, e.g. for looking ahead.
+    fn clone(&self) -> Self {
+        Self {
+            head: self.head,
+            flink: self.flink,
+            blink: self.blink,
+        }
+    }
+}
+
 impl<'a, E, L> Iterator for Iter<'a, E, L>
 where
     E: NtListElement<L>,
@@ -315,6 +789,22 @@ where
     fn last(mut self) -> Option<&'a E> {
         self.next_back()
     }
+
+    /// Returns the bounds on the remaining length of the iterator that are known in *O*(1) time.
+    ///
+    /// The exact length would require walking all remaining elements, which would turn a
+    /// frequently called and supposedly cheap function into an *O*(*n*) operation.
+    /// Hence, the bounds are only exact for zero or one remaining element.
+    /// Use [`NtListHead::len`] if you need the exact length of the entire list.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.flink == self.head.end_marker() {
+            (0, Some(0))
+        } else if self.flink == self.blink {
+            (1, Some(1))
+        } else {
+            (2, None)
+        }
+    }
 }
 
 impl<'a, E, L> DoubleEndedIterator for Iter<'a, E, L>
@@ -400,6 +890,19 @@ where
     fn last(mut self) -> Option<&'a mut E> {
         self.next_back()
     }
+
+    /// Returns the bounds on the remaining length of the iterator that are known in *O*(1) time.
+    ///
+    /// See [`Iter::size_hint`] for the rationale.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if ptr::eq(self.flink, self.head.as_ref().end_marker()) {
+            (0, Some(0))
+        } else if self.flink == self.blink {
+            (1, Some(1))
+        } else {
+            (2, None)
+        }
+    }
 }
 
 impl<'a, E, L> DoubleEndedIterator for IterMut<'a, E, L>
@@ -448,15 +951,23 @@ where
     E: NtListElement<L>,
     L: NtTypedList<T = NtList>,
 {
+    /// An entry that is not part of any list.
+    ///
+    /// This is the value returned by [`new`](Self::new), but usable in `const` and `static`
+    /// initializers, e.g. to place elements in `static` storage.
+    /// `new` cannot be a `const fn` while supporting Rust 1.56, which doesn't allow trait bounds
+    /// on `const fn`s.
+    pub const UNLINKED: Self = Self {
+        flink: ptr::null_mut(),
+        blink: ptr::null_mut(),
+        pin: PhantomPinned,
+    };
+
     /// Allows the creation of an `NtListEntry`, but leaves all fields uninitialized.
     ///
     /// Its fields are only initialized when an entry is pushed to a list.
     pub fn new() -> Self {
-        Self {
-            flink: ptr::null_mut(),
-            blink: ptr::null_mut(),
-            pin: PhantomPinned,
-        }
+        Self::UNLINKED
     }
 
     pub(crate) unsafe fn containing_record<'a>(ptr: *const Self) -> &'a E {
@@ -473,6 +984,48 @@ where
         unsafe { &mut *element_ptr.cast() }
     }
 
+    /// Swaps `entry` with the entry following it in the list.
+    ///
+    /// The entry following `entry` must not be the end marker.
+    #[cfg(feature = "alloc")]
+    pub(crate) unsafe fn swap_with_next(entry: *mut Self) {
+        let next = (*entry).flink;
+
+        // Turn `previous <-> entry <-> next <-> after` into `previous <-> next <-> entry <-> after`.
+        // Both `previous` and `after` may be the end marker.
+        let previous = (*entry).blink;
+        let after = (*next).flink;
+
+        (*previous).flink = next;
+        (*next).blink = previous;
+        (*next).flink = entry;
+        (*entry).blink = next;
+        (*entry).flink = after;
+        (*after).blink = entry;
+
+        debug_assert_eq!((*(*next).blink).flink, next);
+        debug_assert_eq!((*(*entry).flink).blink, entry);
+    }
+
+    /// Leaks `element` and links its entry between `previous` and `next`, which must be adjacent.
+    ///
+    /// Returns the entry of the linked element.
+    #[cfg(feature = "alloc")]
+    pub(crate) unsafe fn link_boxed(
+        element: Box<E>,
+        previous: *mut Self,
+        next: *mut Self,
+    ) -> *mut Self {
+        let entry = NtListHead::entry(Box::leak(element));
+
+        (*entry).flink = next;
+        (*entry).blink = previous;
+        (*previous).flink = entry;
+        (*next).blink = entry;
+
+        entry
+    }
+
     pub(crate) unsafe fn remove(&mut self) {
         let old_flink = self.flink;
         let old_blink = self.blink;
@@ -481,6 +1034,24 @@ where
     }
 }
 
+impl<E, L> Clone for NtListEntry<E, L>
+where
+    E: NtListElement<L>,
+    L: NtTypedList<T = NtList>,
+{
+    /// Returns a new entry that is not part of any list, just like [`NtListEntry::new`].
+    ///
+    /// Copying the links instead would make the clone point into the list of the original
+    /// entry, although the list doesn't point back to it.
+    /// Pushing or removing the clone would then corrupt that list.
+    ///
+    /// This allows deriving [`Clone`] for element structures, with every clone starting out
+    /// unlinked.
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
 impl<E, L> Default for NtListEntry<E, L>
 where
     E: NtListElement<L>,
@@ -490,3 +1061,513 @@ where
         Self::new()
     }
 }
+
+impl<E, L> Eq for NtListEntry<E, L>
+where
+    E: NtListElement<L>,
+    L: NtTypedList<T = NtList>,
+{
+}
+
+impl<E, L> PartialEq for NtListEntry<E, L>
+where
+    E: NtListElement<L>,
+    L: NtTypedList<T = NtList>,
+{
+    /// Returns `true` if both entries have the same forward and backward links.
+    ///
+    /// This only compares the link state of the entries, e.g. to verify that a list has been
+    /// linked as expected.
+    /// It says nothing about the equality of the elements containing the entries.
+    fn eq(&self, other: &Self) -> bool {
+        self.flink == other.flink && self.blink == other.blink
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use moveit::moveit;
+
+    #[derive(NtList)]
+    enum MyList {}
+
+    #[derive(Default, NtListElement)]
+    #[repr(C)]
+    struct MyElement {
+        value: i32,
+        entry: NtListEntry<Self, MyList>,
+    }
+
+    impl MyElement {
+        fn new(value: i32) -> Self {
+            Self {
+                value,
+                ..Default::default()
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "out of bounds for an element")]
+    fn test_wrong_offset() {
+        #[repr(C)]
+        struct WrongElement {
+            entry: NtListEntry<Self, MyList>,
+        }
+
+        // A hand-written implementation with an offset pointing past the end of the element.
+        unsafe impl NtListElement<MyList> for WrongElement {
+            fn offset() -> usize {
+                mem::size_of::<Self>()
+            }
+        }
+
+        let mut element = WrongElement {
+            entry: NtListEntry::new(),
+        };
+
+        moveit! {
+            let mut list = NtListHead::<WrongElement, MyList>::new();
+        }
+
+        unsafe { list.as_mut().push_back(&mut element) };
+    }
+
+    #[test]
+    fn test_clone_entry() {
+        #[derive(Clone, NtListElement)]
+        #[repr(C)]
+        struct CloneableElement {
+            value: i32,
+            entry: NtListEntry<Self, MyList>,
+        }
+
+        let mut element = CloneableElement {
+            value: 1,
+            entry: NtListEntry::new(),
+        };
+
+        moveit! {
+            let mut list = NtListHead::<CloneableElement, MyList>::new();
+        }
+
+        unsafe { list.as_mut().push_back(&mut element) };
+
+        let clone = element.clone();
+        assert_eq!(clone.value, 1);
+        assert!(clone.entry.flink.is_null() && clone.entry.blink.is_null());
+
+        assert_eq!(unsafe { list.as_ref().len() }, 1);
+        assert!(!element.entry.flink.is_null());
+    }
+
+    #[test]
+    fn test_drain_entries() {
+        let mut elements = [0, 1, 2, 3, 4].map(MyElement::new);
+
+        moveit! {
+            let mut list = NtListHead::<MyElement, MyList>::new();
+            let mut other = NtListHead::<MyElement, MyList>::new();
+        }
+
+        for element in elements.iter_mut() {
+            unsafe { list.as_mut().push_back(element) };
+        }
+
+        // Move all odd elements to another list while draining.
+        for entry in unsafe { list.as_mut().drain_entries() } {
+            unsafe {
+                assert!((*entry).flink.is_null() && (*entry).blink.is_null());
+
+                let element = NtListEntry::containing_record_mut(entry);
+                if element.value % 2 == 1 {
+                    other.as_mut().push_back(element);
+                }
+            }
+        }
+
+        assert!(list.as_ref().is_empty());
+        assert_eq!(list.flink, list.blink);
+
+        for (i, element) in [1, 3].into_iter().zip(unsafe { other.as_ref().iter() }) {
+            assert_eq!(i, element.value);
+        }
+
+        assert_eq!(unsafe { other.as_ref().len() }, 2);
+        assert!(unsafe { list.as_mut().drain_entries() }.next().is_none());
+    }
+
+    #[test]
+    fn test_entry_eq() {
+        let mut elements = [0, 1].map(MyElement::new);
+        assert!(elements[0].entry == elements[1].entry);
+
+        moveit! {
+            let mut list = NtListHead::<MyElement, MyList>::new();
+        }
+
+        for element in elements.iter_mut() {
+            unsafe { list.as_mut().push_back(element) };
+        }
+
+        // In a list of two elements, both entries point to the header and each other.
+        assert!(elements[0].entry != elements[1].entry);
+        assert_eq!(elements[0].entry.blink, elements[1].entry.flink);
+        assert!(elements[0].entry == elements[0].entry);
+    }
+
+    #[test]
+    fn test_iter_raw() {
+        let mut elements = [0, 1, 2].map(MyElement::new);
+
+        moveit! {
+            let mut list = NtListHead::<MyElement, MyList>::new();
+        }
+
+        for element in elements.iter_mut() {
+            unsafe { list.as_mut().push_back(element) };
+        }
+
+        let head: &NtListHead<MyElement, MyList> = &list;
+
+        for (i, element) in (0..3).zip(unsafe { head.iter_raw() }) {
+            assert_eq!(i, element.value);
+        }
+
+        assert_eq!(unsafe { head.iter_raw() }.count(), 3);
+    }
+
+    #[test]
+    fn test_retain_adjacent() {
+        let mut elements = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9].map(MyElement::new);
+
+        moveit! {
+            let mut list = NtListHead::<MyElement, MyList>::new();
+        }
+
+        for element in elements.iter_mut() {
+            unsafe { list.as_mut().push_back(element) };
+        }
+
+        // Remove the first element, a run in the middle, and a run ending with the last element.
+        unsafe {
+            list.as_mut()
+                .retain(|element| !matches!(element.value, 0 | 2..=4 | 7..=9))
+        };
+
+        assert_eq!(unsafe { list.as_ref().len() }, 3);
+
+        for (i, element) in [1, 5, 6].into_iter().zip(unsafe { list.as_ref().iter() }) {
+            assert_eq!(i, element.value);
+        }
+
+        for (i, element) in [6, 5, 1]
+            .into_iter()
+            .zip(unsafe { list.as_ref().iter().rev() })
+        {
+            assert_eq!(i, element.value);
+        }
+
+        // Remove all remaining elements in one go.
+        unsafe { list.as_mut().retain(|_| false) };
+        assert!(list.as_ref().is_empty());
+        assert_eq!(list.flink, list.blink);
+    }
+
+    #[test]
+    fn test_size_hint() {
+        let mut elements = [0, 1, 2].map(MyElement::new);
+
+        moveit! {
+            let mut list = NtListHead::<MyElement, MyList>::new();
+        }
+
+        assert_eq!(unsafe { list.as_ref().iter() }.size_hint(), (0, Some(0)));
+
+        for element in elements.iter_mut() {
+            unsafe { list.as_mut().push_back(element) };
+        }
+
+        let mut iter = unsafe { list.as_ref().iter() };
+        assert_eq!(iter.size_hint(), (2, None));
+        iter.next();
+        assert_eq!(iter.size_hint(), (2, None));
+        iter.next_back();
+        assert_eq!(iter.size_hint(), (1, Some(1)));
+        iter.next();
+        assert_eq!(iter.size_hint(), (0, Some(0)));
+
+        let mut iter = unsafe { list.as_mut().iter_mut() };
+        assert_eq!(iter.size_hint(), (2, None));
+        iter.next();
+        iter.next();
+        assert_eq!(iter.size_hint(), (1, Some(1)));
+        iter.next();
+        assert_eq!(iter.size_hint(), (0, Some(0)));
+    }
+
+    #[test]
+    fn test_split_off() {
+        let mut elements = [0, 1, 2, 3, 4].map(MyElement::new);
+
+        moveit! {
+            let mut list = NtListHead::<MyElement, MyList>::new();
+            let mut dest = NtListHead::<MyElement, MyList>::new();
+        }
+
+        for element in elements.iter_mut() {
+            unsafe { list.as_mut().push_back(element) };
+        }
+
+        // Splitting at the length leaves `dest` empty.
+        unsafe { list.as_mut().split_off(5, dest.as_mut()) };
+        assert_eq!(unsafe { list.as_ref().len() }, 5);
+        assert!(dest.as_ref().is_empty());
+
+        unsafe { list.as_mut().split_off(2, dest.as_mut()) };
+
+        for (i, element) in [0, 1].into_iter().zip(unsafe { list.as_ref().iter() }) {
+            assert_eq!(i, element.value);
+        }
+        for (i, element) in [1, 0]
+            .into_iter()
+            .zip(unsafe { list.as_ref().iter().rev() })
+        {
+            assert_eq!(i, element.value);
+        }
+        assert_eq!(unsafe { list.as_ref().len() }, 2);
+
+        for (i, element) in [2, 3, 4].into_iter().zip(unsafe { dest.as_ref().iter() }) {
+            assert_eq!(i, element.value);
+        }
+        for (i, element) in [4, 3, 2]
+            .into_iter()
+            .zip(unsafe { dest.as_ref().iter().rev() })
+        {
+            assert_eq!(i, element.value);
+        }
+        assert_eq!(unsafe { dest.as_ref().len() }, 3);
+
+        // Splitting at 0 moves all elements.
+        dest.as_mut().clear();
+        unsafe { list.as_mut().split_off(0, dest.as_mut()) };
+        assert!(list.as_ref().is_empty());
+        assert_eq!(list.flink, list.blink);
+        assert_eq!(unsafe { dest.as_ref().len() }, 2);
+        assert_eq!(unsafe { dest.as_ref().back() }.unwrap().value, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "split index 3 out of range for list of length 2")]
+    fn test_split_off_out_of_bounds() {
+        let mut elements = [0, 1].map(MyElement::new);
+
+        moveit! {
+            let mut list = NtListHead::<MyElement, MyList>::new();
+            let mut dest = NtListHead::<MyElement, MyList>::new();
+        }
+
+        for element in elements.iter_mut() {
+            unsafe { list.as_mut().push_back(element) };
+        }
+
+        unsafe { list.as_mut().split_off(3, dest.as_mut()) };
+    }
+
+    #[test]
+    fn test_empty_and_init() {
+        static mut LIST: NtListHead<MyElement, MyList> = NtListHead::EMPTY;
+
+        let mut elements = [0, 1, 2].map(MyElement::new);
+        let mut list = unsafe { Pin::new_unchecked(&mut *ptr::addr_of_mut!(LIST)) };
+
+        assert!(list.flink.is_null());
+        list.as_mut().init();
+        assert!(list.as_ref().is_empty());
+
+        for element in elements.iter_mut() {
+            unsafe { list.as_mut().push_back(element) };
+        }
+
+        for (i, element) in (0..3).zip(unsafe { list.as_ref().iter() }) {
+            assert_eq!(i, element.value);
+        }
+    }
+
+    #[test]
+    fn test_single_list_entry_of() {
+        #[derive(NtSingleList)]
+        enum MySingleList {}
+
+        #[derive(Default, NtListElement)]
+        #[repr(C)]
+        struct DualElement {
+            value: i32,
+            entry: NtListEntry<Self, MyList>,
+            single_entry: NtSingleListEntry<Self, MySingleList>,
+        }
+
+        let element = DualElement::default();
+
+        let single_entry =
+            NtListHead::<DualElement, MyList>::single_list_entry_of::<MySingleList>(&element);
+        assert!(ptr::eq(single_entry, &element.single_entry));
+
+        let entry =
+            NtSingleListHead::<DualElement, MySingleList>::list_entry_of::<MyList>(&element);
+        assert!(ptr::eq(entry, &element.entry));
+        let _ = element.value;
+    }
+
+    #[test]
+    fn test_iter_clone() {
+        let mut elements = [0, 1, 2, 3].map(MyElement::new);
+
+        moveit! {
+            let mut list = NtListHead::<MyElement, MyList>::new();
+        }
+
+        for element in elements.iter_mut() {
+            unsafe { list.as_mut().push_back(element) };
+        }
+
+        let mut iter = unsafe { list.as_ref().iter() };
+        iter.next();
+        iter.next_back();
+
+        // Advancing the clone must not affect the original iterator.
+        let lookahead = iter.clone();
+        assert!(lookahead.map(|element| element.value).eq([1, 2]));
+        assert!(iter.rev().map(|element| element.value).eq([2, 1]));
+    }
+
+    #[test]
+    fn test_append_and_prepend_element() {
+        let mut elements = [0, 1, 2].map(MyElement::new);
+
+        moveit! {
+            let mut list = NtListHead::<MyElement, MyList>::new();
+        }
+
+        unsafe {
+            list.as_mut().append_element(&mut elements[1]);
+            list.as_mut().append_element(&mut elements[2]);
+            list.as_mut().prepend_element(&mut elements[0]);
+        }
+
+        for (i, element) in (0..3).zip(unsafe { list.as_ref().iter() }) {
+            assert_eq!(i, element.value);
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "element is already part of a list")]
+    fn test_append_element_twice() {
+        let mut element = MyElement::new(0);
+
+        moveit! {
+            let mut list = NtListHead::<MyElement, MyList>::new();
+        }
+
+        unsafe {
+            list.as_mut().append_element(&mut element);
+            list.as_mut().append_element(&mut element);
+        }
+    }
+
+    #[test]
+    fn test_unlinked_static_element() {
+        static mut ELEMENT: MyElement = MyElement {
+            value: 42,
+            entry: NtListEntry::UNLINKED,
+        };
+
+        moveit! {
+            let mut list = NtListHead::<MyElement, MyList>::new();
+        }
+
+        let element = unsafe { &mut *ptr::addr_of_mut!(ELEMENT) };
+        assert!(element.entry.flink.is_null());
+        unsafe { list.as_mut().push_back(element) };
+
+        assert_eq!(unsafe { list.as_ref().len() }, 1);
+        assert_eq!(unsafe { list.as_ref().front() }.unwrap().value, 42);
+    }
+
+    #[test]
+    fn test_clear_and_unlink() {
+        let mut elements = [0, 1, 2].map(MyElement::new);
+
+        moveit! {
+            let mut list = NtListHead::<MyElement, MyList>::new();
+            let mut other = NtListHead::<MyElement, MyList>::new();
+        }
+
+        for element in elements.iter_mut() {
+            unsafe { list.as_mut().append_element(element) };
+        }
+
+        unsafe { list.as_mut().clear_and_unlink() };
+        assert!(list.as_ref().is_empty());
+
+        for element in elements.iter_mut() {
+            assert!(element.entry.flink.is_null() && element.entry.blink.is_null());
+
+            // This would fail the debug check for linked elements after a plain `clear`.
+            unsafe { other.as_mut().append_element(element) };
+        }
+
+        for (i, element) in (0..3).zip(unsafe { other.as_ref().iter() }) {
+            assert_eq!(i, element.value);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_debug() {
+        use alloc::format;
+
+        let mut elements = [0, 1].map(MyElement::new);
+
+        moveit! {
+            let mut list = NtListHead::<MyElement, MyList>::new();
+        }
+
+        for element in elements.iter_mut() {
+            unsafe { list.as_mut().push_back(element) };
+        }
+
+        // The header must only print its own links.
+        assert_eq!(
+            format!("{:?}", list),
+            format!(
+                "NtListHead {{ flink: {:?}, blink: {:?} }}",
+                list.flink, list.blink
+            )
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "cannot append a list to itself")]
+    fn test_append_to_itself() {
+        let mut elements = [0, 1].map(MyElement::new);
+
+        moveit! {
+            let mut list = NtListHead::<MyElement, MyList>::new();
+        }
+
+        for element in elements.iter_mut() {
+            unsafe { list.as_mut().push_back(element) };
+        }
+
+        // This is exactly the aliasing mistake that `append` guards against in debug builds.
+        unsafe {
+            let ptr = list.as_mut().get_unchecked_mut() as *mut NtListHead<MyElement, MyList>;
+            Pin::new_unchecked(&mut *ptr).append(Pin::new_unchecked(&mut *ptr));
+        }
+    }
+}
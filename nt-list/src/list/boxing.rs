@@ -1,15 +1,25 @@
 // Copyright 2022 Colin Finck <colin@reactos.org>
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use core::marker::PhantomPinned;
+use core::fmt;
+#[cfg(feature = "std")]
+use core::hash::Hash;
+use core::iter::{Cloned, Enumerate, FusedIterator, Rev};
+use core::marker::{PhantomData, PhantomPinned};
+use core::ops::Range;
 use core::pin::Pin;
 use core::ptr;
 
 use alloc::boxed::Box;
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
 use moveit::{new, New};
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
 
 use super::base::{Iter, IterMut, NtListEntry, NtListHead};
 use super::traits::NtList;
+use crate::boxing::try_box;
 use crate::traits::{NtBoxedListElement, NtListElement, NtTypedList};
 
 /// A variant of [`NtListHead`] that boxes every element on insertion.
@@ -56,6 +66,50 @@ where
         })
     }
 
+    /// Creates a new doubly linked list that owns all elements, allocating the header on the heap.
+    ///
+    /// Unlike [`new`](Self::new), this doesn't need `moveit`.
+    /// The heap address of the header is stable, which makes the returned pinned [`Box`] suitable
+    /// for long-lived lists stored in other structures.
+    /// Elements pushed to the list are deallocated when the box is dropped as usual.
+    ///
+    /// This function substitutes [`InitializeListHead`] of the Windows NT API.
+    ///
+    /// [`InitializeListHead`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-initializelisthead
+    pub fn new_boxed() -> Pin<Box<Self>> {
+        let mut boxed = Box::pin(Self(NtListHead {
+            flink: ptr::null_mut(),
+            blink: ptr::null_mut(),
+            pin: PhantomPinned,
+        }));
+
+        let this = unsafe { boxed.as_mut().get_unchecked_mut() };
+        this.0.flink = (this as *mut Self).cast();
+        this.0.blink = this.0.flink;
+
+        boxed
+    }
+
+    /// Initializes a doubly linked list header that owns all elements at the given memory
+    /// location.
+    ///
+    /// Unlike [`new`](Self::new), this doesn't need `moveit` and allows initializing a header
+    /// that is embedded in a larger structure at a stable address, e.g. a device extension.
+    /// Elements pushed to the list are deallocated when the header is dropped as usual.
+    ///
+    /// This function substitutes [`InitializeListHead`] of the Windows NT API.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be valid for writes and properly aligned.
+    /// Any previous value at `ptr` is overwritten without being dropped.
+    /// After this call, the header must never be moved again, just like if it had been pinned.
+    ///
+    /// [`InitializeListHead`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-initializelisthead
+    pub unsafe fn init_at(ptr: *mut Self) {
+        NtListHead::<E, L>::init_at(ptr.cast());
+    }
+
     /// Moves all elements from `other` to the end of the list.
     ///
     /// This reuses all the nodes from `other` and moves them into `self`.
@@ -66,6 +120,57 @@ where
         unsafe { self.inner_mut().append(other.inner_mut()) }
     }
 
+    /// Moves all elements from `other` to the end of the list and returns how many elements
+    /// have been moved.
+    ///
+    /// This works just like [`append`](Self::append), but needs to traverse `other` once to
+    /// count its elements.
+    /// Use it instead of calling [`len`](Self::len) on `other` before appending, which would
+    /// result in the same traversal.
+    ///
+    /// This operation computes in *O*(*m*) time, where *m* is the length of `other`.
+    pub fn append_counted(self: Pin<&mut Self>, other: Pin<&mut Self>) -> usize {
+        let count = other.as_ref().len();
+        self.append(other);
+        count
+    }
+
+    /// Moves all elements yielded by `iter` to the end of the list.
+    ///
+    /// Unlike [`append`](Self::append), this accepts any iterator over boxed elements,
+    /// e.g. one that lazily pops all elements of another list:
+    /// `list.append_from(core::iter::from_fn(|| other.as_mut().pop_front()))`.
+    ///
+    /// This operation computes in *O*(*m*) time, where *m* is the number of elements
+    /// yielded by `iter`.
+    pub fn append_from<I>(mut self: Pin<&mut Self>, iter: I)
+    where
+        I: IntoIterator<Item = Box<E>>,
+    {
+        self.extend(iter)
+    }
+
+    /// Returns a vector of mutable references to all elements of the list, in order.
+    ///
+    /// This provides indexed access to the elements for algorithms that need it.
+    /// Every reference points to a different element, and the vector borrows the list
+    /// mutably for as long as it exists.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn as_mut_vec(self: Pin<&mut Self>) -> Vec<&mut E> {
+        self.iter_mut().collect()
+    }
+
+    /// Returns a vector of references to all elements of the list, in order.
+    ///
+    /// This provides indexed access to the elements for algorithms that need it.
+    /// The vector borrows the list for as long as it exists.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn as_ref_vec(self: Pin<&Self>) -> Vec<&E> {
+        self.iter().collect()
+    }
+
     /// Provides a reference to the last element, or `None` if the list is empty.
     ///
     /// This operation computes in *O*(*1*) time.
@@ -73,6 +178,16 @@ where
         unsafe { self.inner().back() }
     }
 
+    /// Returns a pointer to the entry of the last element, or `None` if the list is empty.
+    ///
+    /// This is useful for maintaining an index of entries outside the list.
+    /// The returned pointer is only valid as long as the element is part of the list.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn back_entry(self: Pin<&Self>) -> Option<*const NtListEntry<E, L>> {
+        self.inner().back_entry()
+    }
+
     /// Provides a mutable reference to the last element, or `None` if the list is empty.
     ///
     /// This operation computes in *O*(*1*) time.
@@ -80,469 +195,4465 @@ where
         unsafe { self.inner_mut().back_mut() }
     }
 
+    /// Provides a reference to the last element together with a pointer to its entry, or `None`
+    /// if the list is empty.
+    ///
+    /// This combines [`back`](Self::back) and [`back_entry`](Self::back_entry) for maintaining an
+    /// index of entries outside the list.
+    /// The returned pointer is only valid as long as the element is part of the list.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn back_with_entry(self: Pin<&Self>) -> Option<(&E, *const NtListEntry<E, L>)> {
+        self.inner()
+            .back_entry()
+            .map(|entry| (unsafe { NtListEntry::containing_record(entry) }, entry))
+    }
+
     /// Removes all elements from the list, deallocating their memory.
     ///
+    /// Elements are dropped in list order, from the first to the last one.
+    /// The same order applies when the list itself is dropped.
+    ///
+    /// If the `Drop` handler of an element panics, the memory of that element is still
+    /// deallocated, and all elements after it stay in the list, which is still valid.
+    /// Call `clear` again, e.g. from the handler that caught the panic via `catch_unwind`, to
+    /// drop them as well.
+    ///
     /// Unlike [`NtListHead::clear`], this operation computes in *O*(*n*) time, because it
     /// needs to traverse all elements to deallocate them.
-    pub fn clear(mut self: Pin<&mut Self>) {
-        let end_marker = self.as_mut().inner_mut().end_marker_mut();
+    pub fn clear(self: Pin<&mut Self>) {
+        self.clear_with(|_| {})
+    }
 
-        // Get the link to the first element before it's being reset.
-        let mut current = self.0.flink;
+    /// Removes all elements from the list, calling `f` on each element right before it is
+    /// dropped and deallocated.
+    ///
+    /// This allows releasing per-element resources in list order, from the first to the last
+    /// element, while the memory of each element is still valid.
+    /// `f` is only invoked by this function and not when the list is dropped or elements are
+    /// removed in any other way.
+    ///
+    /// If `f` panics, the element passed to it has already been removed from the list and is
+    /// leaked.
+    /// If the `Drop` handler of an element panics, the memory of that element is still
+    /// deallocated.
+    /// In both cases, all remaining elements stay in the list, just like for a panic in
+    /// [`clear`](Self::clear).
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn clear_with<F>(mut self: Pin<&mut Self>, mut f: F)
+    where
+        F: FnMut(&mut E),
+    {
+        let end_marker = self.as_mut().inner_mut().end_marker_mut();
 
-        // Make the list appear empty before deallocating any element.
-        // By doing this here and not at the very end, we guard against the following scenario:
+        // Unlink every element before deallocating it.
+        // By doing this for each element and not for the entire list at the very end, we guard
+        // against the following scenario:
         //
         // 1. We deallocate an element.
         // 2. The `Drop` handler of that element is called and panics.
         // 3. Consequently, the `Drop` handler of `NtBoxingListHead` is called and removes all elements.
         // 4. While removing elements, the just dropped element is dropped again.
         //
-        // By clearing the list at the beginning, the `Drop` handler of `NtBoxingListHead` won't find any
-        // elements, and thereby it won't drop any elements.
-        self.inner_mut().clear();
+        // As the element is no longer part of the list when it is dropped, the `Drop` handler of
+        // `NtBoxingListHead` only finds the remaining elements, which still form a valid list.
+        loop {
+            let current = self.0.flink;
+            if current == end_marker {
+                break;
+            }
 
-        // Traverse the list in the old-fashioned way and deallocate each element.
-        while current != end_marker {
             unsafe {
+                (*current).remove();
+
                 let element = NtListEntry::containing_record_mut(current);
-                current = (*current).flink;
+                f(element);
                 drop(Box::from_raw(element));
             }
         }
     }
 
-    /// Provides a reference to the first element, or `None` if the list is empty.
+    /// Creates a deep copy of the list, cloning every element in order.
     ///
-    /// This operation computes in *O*(*1*) time.
-    pub fn front(self: Pin<&Self>) -> Option<&E> {
-        unsafe { self.inner().front() }
+    /// The returned [`New`] needs to be placed, e.g. via `moveit!`, to obtain the new list.
+    /// All cloned elements are boxed individually and linked anew, so the clone shares no
+    /// memory with `self` and both lists can be modified and dropped independently.
+    ///
+    /// The [`Clone`] trait cannot be implemented for `NtBoxingListHead`:
+    /// [`Clone::clone`] returns the new list by value, but moving a doubly linked list header
+    /// invalidates the links of its first and last element, which point back to the header.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn clone_into_new(self: Pin<&Self>) -> impl New<Output = Self> + '_
+    where
+        E: Clone,
+    {
+        Self::new().with(move |mut this| this.extend(self.iter().cloned()))
     }
 
-    /// Provides a mutable reference to the first element, or `None` if the list is empty.
+    /// Returns an iterator yielding clones of each element of the list, in order.
     ///
-    /// This operation computes in *O*(*1*) time.
-    pub fn front_mut(self: Pin<&mut Self>) -> Option<&mut E> {
-        unsafe { self.inner_mut().front_mut() }
+    /// This is equivalent to `iter().cloned()`.
+    /// Every element is only cloned when it is yielded.
+    pub fn cloned(self: Pin<&Self>) -> Cloned<Iter<'_, E, L>>
+    where
+        E: Clone,
+    {
+        self.iter().cloned()
     }
 
-    fn inner(self: Pin<&Self>) -> Pin<&NtListHead<E, L>> {
-        unsafe { Pin::new_unchecked(&self.get_ref().0) }
+    /// Fills `out` with pointers to the entries of the list in order and returns how many
+    /// pointers have been written.
+    ///
+    /// If `out` is too small to hold all entries, only the first `out.len()` entries are written
+    /// and the rest of the list is ignored.
+    /// Slots of `out` beyond the returned count are left untouched.
+    ///
+    /// This operation computes in *O*(*min(n, m)*) time, where *m* is the length of `out`.
+    pub fn collect_entries(self: Pin<&Self>, out: &mut [*const NtListEntry<E, L>]) -> usize {
+        unsafe { self.inner().collect_entries(out) }
     }
 
-    fn inner_mut(self: Pin<&mut Self>) -> Pin<&mut NtListHead<E, L>> {
-        unsafe { Pin::new_unchecked(&mut self.get_unchecked_mut().0) }
+    /// Appends clones of all elements of the list to `buf`, in order.
+    ///
+    /// Existing elements of `buf` are kept, and its capacity is reused.
+    /// This avoids allocating a new [`Vec`] for every snapshot of the list, e.g. in a polling loop.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn collect_into(self: Pin<&Self>, buf: &mut Vec<E>)
+    where
+        E: Clone,
+    {
+        buf.extend(self.iter().cloned());
     }
 
-    /// Returns `true` if the list is empty.
+    /// Appends references to all elements of the list to `buf`, in order.
     ///
-    /// This function substitutes [`IsListEmpty`] of the Windows NT API.
+    /// Just like [`collect_into`](Self::collect_into), this reuses the capacity of `buf`.
     ///
-    /// This operation computes in *O*(*1*) time.
+    /// This operation computes in *O*(*n*) time.
+    pub fn collect_refs_into<'a>(self: Pin<&'a Self>, buf: &mut Vec<&'a E>) {
+        buf.extend(self.iter());
+    }
+
+    /// Returns `true` if the element at the given address is part of this list.
     ///
-    /// [`IsListEmpty`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-islistempty
-    pub fn is_empty(self: Pin<&Self>) -> bool {
-        self.inner().is_empty()
+    /// Unlike a comparison of element values, this checks for the identity of `element`.
+    /// `element` does not need to point to a valid element. It is only compared against the
+    /// addresses of all list elements and never dereferenced.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn contains_ptr(self: Pin<&Self>, element: *const E) -> bool {
+        // This is the canonical implementation of `wrapping_byte_add`
+        let entry = element
+            .cast::<u8>()
+            .wrapping_add(E::offset())
+            .cast::<NtListEntry<E, L>>();
+
+        unsafe { self.inner().contains_entry(entry) }
     }
 
-    /// Returns an iterator yielding references to each element of the list.
-    pub fn iter(self: Pin<&Self>) -> Iter<E, L> {
-        unsafe { self.inner().iter() }
+    /// Returns a [`CursorMut`] positioned on the given element.
+    ///
+    /// This is useful if `element` has been located via some external index, as the cursor can
+    /// immediately insert and remove elements around it without traversing the list from its
+    /// front.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    ///
+    /// # Safety
+    ///
+    /// `element` must be part of this list.
+    /// Passing any other element is undefined behavior, which is checked in debug builds.
+    /// In particular, [`CursorMut::remove_current`] would otherwise reconstruct a [`Box`] for an
+    /// element that has never been boxed.
+    pub unsafe fn cursor_at(mut self: Pin<&mut Self>, element: &mut E) -> CursorMut<'_, E, L> {
+        let end_marker = self.as_mut().inner_mut().end_marker_mut();
+        let entry = NtListHead::entry(element);
+
+        debug_assert!(
+            self.as_ref().inner().contains_entry(entry),
+            "element is not part of this list"
+        );
+
+        CursorMut {
+            end_marker,
+            current: entry,
+            phantom: PhantomData,
+        }
     }
 
-    /// Returns an iterator yielding mutable references to each element of the list.
-    pub fn iter_mut(self: Pin<&mut Self>) -> IterMut<E, L> {
-        unsafe { self.inner_mut().iter_mut() }
+    /// Removes consecutive elements for which `same(a, b)` returns `true`, keeping only the last
+    /// element of each run.
+    ///
+    /// `same` is passed every pair of adjacent elements, the earlier one first.
+    /// If it returns `true`, the earlier element is removed and dropped.
+    /// This is the counterpart to a `dedup_by` that keeps the first element of each run, and is
+    /// useful when later elements supersede earlier ones.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn dedup_keep_last_by<F>(mut self: Pin<&mut Self>, mut same: F)
+    where
+        F: FnMut(&mut E, &mut E) -> bool,
+    {
+        let end_marker = self.as_mut().inner_mut().end_marker_mut();
+        let mut current = self.0.flink;
+
+        while current != end_marker {
+            unsafe {
+                let next = (*current).flink;
+                if next == end_marker {
+                    break;
+                }
+
+                let element = NtListEntry::containing_record_mut(current);
+                let next_element = NtListEntry::containing_record_mut(next);
+
+                if same(element, next_element) {
+                    (*current).remove();
+                    drop(Box::from_raw(element));
+                }
+
+                current = next;
+            }
+        }
     }
 
-    /// Counts all elements and returns the length of the list.
+    /// Returns the number of elements following the last element that matches the predicate,
+    /// or `None` if no element matches.
+    ///
+    /// This is the distance of the matching element to the back of the list, e.g. 0 if the last
+    /// element matches.
+    /// Use [`rposition`](Self::rposition) to get the index of that element instead.
     ///
     /// This operation computes in *O*(*n*) time.
-    pub fn len(self: Pin<&Self>) -> usize {
-        unsafe { self.inner().len() }
+    pub fn distance_back_to<P>(self: Pin<&Self>, p: P) -> Option<usize>
+    where
+        P: FnMut(&E) -> bool,
+    {
+        self.iter().rev().position(p)
     }
 
-    /// Removes the last element from the list and returns it, or `None` if the list is empty.
+    /// Returns the number of elements preceding the first element that matches the predicate,
+    /// or `None` if no element matches.
     ///
-    /// This function substitutes [`RemoveTailList`] of the Windows NT API.
+    /// This is the distance of the matching element to the front of the list, and therefore
+    /// equals its index.
     ///
-    /// This operation computes in *O*(*1*) time.
+    /// This operation computes in *O*(*n*) time.
+    pub fn distance_to<P>(self: Pin<&Self>, p: P) -> Option<usize>
+    where
+        P: FnMut(&E) -> bool,
+    {
+        self.iter().position(p)
+    }
+
+    /// Removes all elements from the list and returns them in order.
     ///
-    /// [`RemoveTailList`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-removetaillist
-    pub fn pop_back(self: Pin<&mut Self>) -> Option<Box<E>> {
-        unsafe {
-            self.inner_mut()
-                .pop_back()
-                .map(|element| Box::from_raw(element))
+    /// The returned vector is allocated once with the exact length of the list.
+    /// Unlike calling [`pop_front`](Self::pop_front) repeatedly, the list header is only
+    /// updated once.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn drain_collect(mut self: Pin<&mut Self>) -> Vec<Box<E>> {
+        let mut elements = Vec::with_capacity(self.as_ref().len());
+        let end_marker = self.as_mut().inner_mut().end_marker_mut();
+        let mut current = self.0.flink;
+
+        self.as_mut().inner_mut().clear();
+
+        while current != end_marker {
+            unsafe {
+                let element = NtListEntry::containing_record_mut(current);
+                current = (*current).flink;
+                elements.push(Box::from_raw(element));
+            }
         }
+
+        elements
     }
 
-    /// Removes the first element from the list and returns it, or `None` if the list is empty.
+    /// Removes the elements in the given index range from the list and returns them as an
+    /// iterator.
     ///
-    /// This function substitutes [`RemoveHeadList`] of the Windows NT API.
+    /// The removed elements are unlinked from the list all at once before the iterator is
+    /// returned.
+    /// When the iterator is dropped before yielding all removed elements, the remaining ones
+    /// are deallocated.
     ///
-    /// This operation computes in *O*(*1*) time.
+    /// # Panics
     ///
-    /// [`RemoveHeadList`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-removeheadlist
-    pub fn pop_front(self: Pin<&mut Self>) -> Option<Box<E>> {
-        unsafe {
-            self.inner_mut()
-                .pop_front()
-                .map(|element| Box::from_raw(element))
+    /// Panics if the start of the range is greater than its end or if the end of the range is
+    /// greater than the length of the list.
+    ///
+    /// This operation computes in *O*(*end*) time.
+    pub fn drain_range(self: Pin<&mut Self>, range: Range<usize>) -> DrainRange<E, L> {
+        let count = range.len();
+        let (first, last) = self.range_entries(range);
+
+        if count > 0 {
+            // Unlink all elements from `first` to `last` in one go.
+            unsafe {
+                let previous = (*first).blink;
+                let after = (*last).flink;
+                (*previous).flink = after;
+                (*after).blink = previous;
+            }
+        }
+
+        DrainRange {
+            front: first,
+            back: last,
+            remaining: count,
         }
     }
 
-    /// Appends an element to the back of the list.
+    /// Returns an iterator that removes and yields elements from the front of the list as long
+    /// as the predicate `p` returns `true` for them.
     ///
-    /// This function substitutes [`InsertTailList`] of the Windows NT API.
+    /// The first element for which `p` returns `false` stays in the list, and so do all elements
+    /// after it.
+    /// Elements are only removed while the iterator is consumed, one at a time, so dropping it
+    /// early leaves all elements in the list that have not been yielded yet.
+    /// This is the batch form of [`pop_front_if`](Self::pop_front_if).
+    /// The iterator is fused, so `p` is never called again after it has returned `false` once.
+    ///
+    /// Iterating all removed elements computes in *O*(*k*) time, where *k* is the number of
+    /// removed elements.
+    pub fn drain_while<'a, P>(
+        mut self: Pin<&'a mut Self>,
+        mut p: P,
+    ) -> impl FusedIterator<Item = Box<E>> + 'a
+    where
+        P: FnMut(&E) -> bool + 'a,
+    {
+        core::iter::from_fn(move || self.as_mut().pop_front_if(|element| p(element))).fuse()
+    }
+
+    /// Returns a mutable reference to the element that contains the given entry.
+    ///
+    /// This allows code that keeps raw entry pointers (e.g. in a custom index) to get back to
+    /// the elements without computing the offset of the entry field itself.
     ///
     /// This operation computes in *O*(*1*) time.
     ///
-    /// [`InsertTailList`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-inserttaillist
-    pub fn push_back(self: Pin<&mut Self>, element: E) {
-        let boxed_element = Box::new(element);
-        unsafe { self.inner_mut().push_back(Box::leak(boxed_element)) }
+    /// # Safety
+    ///
+    /// `entry` must point to the entry of an element that is part of this list.
+    /// This is checked in debug builds, but passing any other pointer is undefined behavior in
+    /// release builds.
+    pub unsafe fn element_mut_from_entry(
+        self: Pin<&mut Self>,
+        entry: *mut NtListEntry<E, L>,
+    ) -> &mut E {
+        debug_assert!(
+            self.as_ref().inner().contains_entry(entry),
+            "entry is not part of this list"
+        );
+
+        NtListEntry::containing_record_mut(entry)
     }
 
-    /// Appends an element to the front of the list.
+    /// Returns a pointer to the entry of the given element that is used for this list.
     ///
-    /// This function substitutes [`InsertHeadList`] of the Windows NT API.
+    /// This doesn't check whether `element` is part of any list.
     ///
     /// This operation computes in *O*(*1*) time.
-    ///
-    /// [`InsertHeadList`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-insertheadlist
-    pub fn push_front(self: Pin<&mut Self>, element: E) {
-        let boxed_element = Box::new(element);
-        unsafe { self.inner_mut().push_front(Box::leak(boxed_element)) }
+    pub fn entry_of(element: &E) -> *const NtListEntry<E, L> {
+        NtListHead::entry_of(element)
     }
 
-    /// Retains only the elements specified by the predicate, passing a mutable reference to it.
+    /// Compares the elements of this list with those of `other` using the predicate `f`.
     ///
-    /// In other words, remove all elements `e` for which `f(&mut e)` returns `false`.
-    /// This method operates in place, visiting each element exactly once in the original order,
-    /// and preserves the order of the retained elements.
+    /// Returns `true` if both lists have the same length and `f` returns `true` for every pair
+    /// of elements at the same position.
+    /// Comparison stops at the first pair for which `f` returns `false`.
     ///
-    /// This function substitutes [`RemoveEntryList`] of the Windows NT API.
+    /// This is useful if `E` does not implement [`PartialEq`] or if some fields of the elements
+    /// shall be ignored.
     ///
     /// This operation computes in *O*(*n*) time.
-    ///
-    /// [`RemoveEntryList`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-removeentrylist
-    pub fn retain<F>(self: Pin<&mut Self>, mut f: F)
+    pub fn eq_by<F>(self: Pin<&Self>, other: Pin<&Self>, mut f: F) -> bool
     where
-        F: FnMut(&mut E) -> bool,
+        F: FnMut(&E, &E) -> bool,
     {
-        for element in self.iter_mut() {
-            if !f(element) {
-                let entry = NtListHead::entry(element);
+        let mut iter = self.iter();
+        let mut other_iter = other.iter();
 
-                unsafe {
-                    (*entry).remove();
-                    drop(Box::from_raw(element));
+        loop {
+            match (iter.next(), other_iter.next()) {
+                (Some(element), Some(other_element)) => {
+                    if !f(element, other_element) {
+                        return false;
+                    }
                 }
+                (None, None) => return true,
+                _ => return false,
             }
         }
     }
-}
 
-impl<E, L> Drop for NtBoxingListHead<E, L>
-where
-    E: NtBoxedListElement<L = L> + NtListElement<L>,
-    L: NtTypedList<T = NtList>,
-{
-    fn drop(&mut self) {
-        let pinned = unsafe { Pin::new_unchecked(self) };
+    /// Appends all elements yielded by `iter` to the end of the list and returns how many
+    /// elements have been appended.
+    ///
+    /// This works just like the [`Extend`] implementation, but is useful when `iter` has no
+    /// known length and the number of appended elements is still needed.
+    ///
+    /// This operation computes in *O*(*m*) time, where *m* is the number of elements
+    /// yielded by `iter`.
+    pub fn extend_count<I>(mut self: Pin<&mut Self>, iter: I) -> usize
+    where
+        I: IntoIterator<Item = E>,
+    {
+        let mut count = 0;
+        self.extend(iter.into_iter().inspect(|_| count += 1));
+        count
+    }
 
-        for element in pinned.iter_mut() {
-            // Reconstruct the `Box` we created in push_back/push_front and let it leave the scope
-            // to call its Drop handler and deallocate the element gracefully.
+    /// Removes all elements for which `f(&mut e)` returns `true` and returns them in order,
+    /// together with the number of elements that have been kept.
+    ///
+    /// This visits each element exactly once, so the caller gets the length of the remaining
+    /// list without traversing it again via [`len`](Self::len).
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn extract_if_counted<F>(mut self: Pin<&mut Self>, mut f: F) -> (Vec<Box<E>>, usize)
+    where
+        F: FnMut(&mut E) -> bool,
+    {
+        let end_marker = self.as_mut().inner_mut().end_marker_mut();
+        let mut current = self.0.flink;
+        let mut extracted = Vec::new();
+        let mut kept = 0;
+
+        while current != end_marker {
             unsafe {
-                drop(Box::from_raw(element));
+                // Store the next pointer before the current element may be unlinked.
+                let next = (*current).flink;
+                let element = NtListEntry::containing_record_mut(current);
+
+                if f(element) {
+                    (*current).remove();
+                    extracted.push(Box::from_raw(element));
+                } else {
+                    kept += 1;
+                }
+
+                current = next;
             }
         }
+
+        (extracted, kept)
     }
-}
 
-impl<E, L> Extend<Box<E>> for Pin<&mut NtBoxingListHead<E, L>>
-where
-    E: NtBoxedListElement<L = L> + NtListElement<L>,
-    L: NtTypedList<T = NtList>,
-{
-    fn extend<T>(&mut self, iter: T)
+    /// Returns a reference to the first element matching the predicate, or `None` if no element
+    /// matches.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn find<P>(self: Pin<&Self>, mut p: P) -> Option<&E>
     where
-        T: IntoIterator<Item = Box<E>>,
+        P: FnMut(&E) -> bool,
+    {
+        self.iter().find(|element| p(element))
+    }
+
+    /// Returns a mutable reference to the first element matching the predicate, or `None` if no
+    /// element matches.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn find_mut<P>(self: Pin<&mut Self>, mut p: P) -> Option<&mut E>
+    where
+        P: FnMut(&E) -> bool,
+    {
+        self.iter_mut().find(|element| p(element))
+    }
+
+    /// Returns a reference to the first element that is equal to an element before it, or `None`
+    /// if all elements are distinct.
+    ///
+    /// Every element is compared with all elements before it, so this only requires [`PartialEq`].
+    /// It is meant for validating invariants in tests and assertions, not for large lists.
+    ///
+    /// This operation computes in *O*(*n*²) time.
+    pub fn first_duplicate(self: Pin<&Self>) -> Option<&E>
+    where
+        E: PartialEq,
+    {
+        self.iter()
+            .enumerate()
+            .find(|(index, element)| self.iter().take(*index).any(|other| other == *element))
+            .map(|(_, element)| element)
+    }
+
+    /// Folds every element into an accumulator by applying `f`, in order, and returns the final
+    /// accumulator.
+    ///
+    /// This is equivalent to `iter().fold(init, f)`.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn fold<B, F>(self: Pin<&Self>, init: B, f: F) -> B
+    where
+        F: FnMut(B, &E) -> B,
+    {
+        self.iter().fold(init, f)
+    }
+
+    /// Calls `f` with an [`ElementCursor`] for every element in order.
+    ///
+    /// The [`ElementCursor`] provides mutable access to its element and can insert new elements
+    /// next to it or remove it from the list.
+    /// This generalizes [`retain_until`](Self::retain_until) for closures that also need to
+    /// insert elements during the traversal.
+    /// The link to the next element is saved before `f` is called, and none of these operations
+    /// touch the saved element, so the traversal stays intact.
+    /// Newly inserted elements are not visited.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn for_each_with_cursor<F>(mut self: Pin<&mut Self>, mut f: F)
+    where
+        F: FnMut(ElementCursor<'_, E, L>),
     {
         let end_marker = self.as_mut().inner_mut().end_marker_mut();
-        let mut previous = self.as_ref().inner().blink;
+        let mut current = self.0.flink;
 
-        for element in iter.into_iter() {
-            // We could use `NtBoxingListHead::push_back` here, but this manual implementation
-            // is slightly optimized (doesn't modify list head's `blink` on every iteration).
+        while current != end_marker {
+            let next = unsafe { (*current).flink };
+
+            f(ElementCursor {
+                entry: current,
+                insert_position: current,
+                phantom: PhantomData,
+            });
+
+            current = next;
+        }
+    }
+
+    /// Creates a new doubly linked list that owns all elements of the given array.
+    ///
+    /// The returned [`New`] needs to be placed, e.g. via `moveit!`, to obtain the list.
+    /// The first array element becomes the first list element.
+    ///
+    /// This is the counterpart to the [`From`] implementation of [`NtBoxingSingleListHead`],
+    /// which cannot be provided for the doubly linked list, because it needs to be pinned.
+    ///
+    /// [`NtBoxingSingleListHead`]: crate::single_list::NtBoxingSingleListHead
+    pub fn from_array<const N: usize>(array: [E; N]) -> impl New<Output = Self> {
+        Self::new().with(move |mut this| this.extend(array))
+    }
+
+    /// Provides a reference to the first element, or `None` if the list is empty.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn front(self: Pin<&Self>) -> Option<&E> {
+        unsafe { self.inner().front() }
+    }
+
+    /// Provides mutable references to the first and the last element at the same time.
+    ///
+    /// Returns `(None, None)` if the list is empty.
+    /// If the list contains just a single element, it is only returned as the first element,
+    /// i.e. `(Some(element), None)`, to avoid handing out two aliasing mutable references.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn front_back_mut(self: Pin<&mut Self>) -> (Option<&mut E>, Option<&mut E>) {
+        if self.as_ref().is_empty() {
+            return (None, None);
+        }
+
+        let flink = self.0.flink;
+        let blink = self.0.blink;
+
+        unsafe {
+            let front = NtListEntry::containing_record_mut(flink);
+
+            if flink == blink {
+                (Some(front), None)
+            } else {
+                (Some(front), Some(NtListEntry::containing_record_mut(blink)))
+            }
+        }
+    }
+
+    /// Returns a pointer to the entry of the first element, or `None` if the list is empty.
+    ///
+    /// This is useful for maintaining an index of entries outside the list.
+    /// The returned pointer is only valid as long as the element is part of the list.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn front_entry(self: Pin<&Self>) -> Option<*const NtListEntry<E, L>> {
+        self.inner().front_entry()
+    }
+
+    /// Provides a mutable reference to the first element, or `None` if the list is empty.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn front_mut(self: Pin<&mut Self>) -> Option<&mut E> {
+        unsafe { self.inner_mut().front_mut() }
+    }
+
+    /// Provides a reference to the first element together with a pointer to its entry, or `None`
+    /// if the list is empty.
+    ///
+    /// This combines [`front`](Self::front) and [`front_entry`](Self::front_entry) for maintaining an
+    /// index of entries outside the list.
+    /// The returned pointer is only valid as long as the element is part of the list.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn front_with_entry(self: Pin<&Self>) -> Option<(&E, *const NtListEntry<E, L>)> {
+        self.inner()
+            .front_entry()
+            .map(|entry| (unsafe { NtListEntry::containing_record(entry) }, entry))
+    }
+
+    /// Returns `true` if any two elements of the list are equal.
+    ///
+    /// See [`first_duplicate`](Self::first_duplicate) for details.
+    ///
+    /// This operation computes in *O*(*n*²) time.
+    pub fn has_duplicates(self: Pin<&Self>) -> bool
+    where
+        E: PartialEq,
+    {
+        self.first_duplicate().is_some()
+    }
+
+    /// Builds a map from the key returned by `f` for each element to a reference to that element.
+    ///
+    /// The list is traversed once.
+    /// If several elements have the same key, the last one of them wins.
+    /// Use [`index_by_multi`](Self::index_by_multi) to keep all of them.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn index_by<K, F>(self: Pin<&Self>, mut f: F) -> HashMap<K, &E>
+    where
+        K: Eq + Hash,
+        F: FnMut(&E) -> K,
+    {
+        self.iter().map(|element| (f(element), element)).collect()
+    }
+
+    /// Builds a map from the key returned by `f` for each element to references to all elements
+    /// with that key, in list order.
+    ///
+    /// The list is traversed once.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn index_by_multi<K, F>(self: Pin<&Self>, mut f: F) -> HashMap<K, Vec<&E>>
+    where
+        K: Eq + Hash,
+        F: FnMut(&E) -> K,
+    {
+        let mut map = HashMap::<K, Vec<&E>>::new();
+
+        for element in self.iter() {
+            map.entry(f(element)).or_default().push(element);
+        }
+
+        map
+    }
+
+    fn inner(self: Pin<&Self>) -> Pin<&NtListHead<E, L>> {
+        unsafe { Pin::new_unchecked(&self.get_ref().0) }
+    }
+
+    fn inner_mut(self: Pin<&mut Self>) -> Pin<&mut NtListHead<E, L>> {
+        unsafe { Pin::new_unchecked(&mut self.get_unchecked_mut().0) }
+    }
+
+    /// Returns `true` if the list is empty.
+    ///
+    /// This function substitutes [`IsListEmpty`] of the Windows NT API.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    ///
+    /// [`IsListEmpty`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-islistempty
+    pub fn is_empty(self: Pin<&Self>) -> bool {
+        self.inner().is_empty()
+    }
+
+    /// Returns an iterator yielding references to each element of the list.
+    pub fn iter(self: Pin<&Self>) -> Iter<E, L> {
+        unsafe { self.inner().iter() }
+    }
+
+    /// Returns an iterator yielding references to each element of the list, from the last to
+    /// the first one.
+    ///
+    /// This is equivalent to `iter().rev()`.
+    pub fn iter_back(self: Pin<&Self>) -> Rev<Iter<'_, E, L>> {
+        self.iter().rev()
+    }
+
+    /// Returns an iterator yielding mutable references to each element of the list, from the
+    /// last to the first one.
+    ///
+    /// This is equivalent to `iter_mut().rev()`.
+    pub fn iter_back_mut(self: Pin<&mut Self>) -> Rev<IterMut<'_, E, L>> {
+        self.iter_mut().rev()
+    }
+
+    /// Returns an endless iterator yielding references to each element of the list, starting over
+    /// at the first element after the last one.
+    ///
+    /// This is useful for round-robin scheduling over the elements.
+    /// If the list is empty, the iterator yields nothing.
+    /// Otherwise, it never ends, so callers must bound their consumption, e.g. via
+    /// [`Iterator::take`].
+    ///
+    /// Every iteration step computes in *O*(*1*) time.
+    pub fn iter_circular(self: Pin<&Self>) -> impl Iterator<Item = &E> + '_ {
+        let end_marker = self.inner().end_marker();
+        let mut current = self.0.flink as *const NtListEntry<E, L>;
+
+        core::iter::from_fn(move || {
+            if self.is_empty() {
+                return None;
+            }
+
+            if current == end_marker {
+                // Skip the header and start over at the first element.
+                current = self.0.flink;
+            }
+
+            let element = unsafe { NtListEntry::containing_record(current) };
+            current = unsafe { (*current).flink };
+            Some(element)
+        })
+    }
+
+    /// Returns an iterator yielding the index and a reference to each element of the list.
+    ///
+    /// This is equivalent to `iter().enumerate()`.
+    pub fn iter_enumerated(self: Pin<&Self>) -> Enumerate<Iter<'_, E, L>> {
+        self.iter().enumerate()
+    }
+
+    /// Returns an iterator yielding the index and a mutable reference to each element of the
+    /// list.
+    ///
+    /// This is equivalent to `iter_mut().enumerate()`.
+    pub fn iter_enumerated_mut(self: Pin<&mut Self>) -> Enumerate<IterMut<'_, E, L>> {
+        self.iter_mut().enumerate()
+    }
+
+    /// Returns an iterator yielding mutable references to each element of the list.
+    pub fn iter_mut(self: Pin<&mut Self>) -> IterMut<E, L> {
+        unsafe { self.inner_mut().iter_mut() }
+    }
+
+    /// Returns an iterator yielding references to every `step`-th element of the list, starting
+    /// with the first one.
+    ///
+    /// This is equivalent to `iter().step_by(step)`.
+    /// Skipped elements still need to be traversed, so iterating all of them computes in
+    /// *O*(*n*) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is 0.
+    pub fn iter_step_by(self: Pin<&Self>, step: usize) -> impl Iterator<Item = &E> + '_ {
+        self.iter().step_by(step)
+    }
+
+    /// Returns an iterator yielding a [`RemovableElement`] for each element of the list.
+    ///
+    /// Each [`RemovableElement`] provides mutable access to its element and can remove it from
+    /// the list, returning the owned element.
+    /// This lets the caller decide during iteration which elements to remove, e.g. based on state
+    /// accumulated from previous elements.
+    /// The list stays consistent no matter which elements are removed and in which order.
+    pub fn iter_with_remover(mut self: Pin<&mut Self>) -> IterWithRemover<'_, E, L> {
+        let end_marker = self.as_mut().inner_mut().end_marker_mut();
+        let current = self.0.flink;
+
+        IterWithRemover {
+            end_marker,
+            current,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Detaches all elements from the list without deallocating them and returns a pointer to
+    /// the entry of the first element, or a null pointer if the list was empty.
+    ///
+    /// The list is empty afterwards and can be reused.
+    /// The detached entries stay linked to each other, but the `blink` of the first entry and
+    /// the `flink` of the last entry are set to null, as the list header they pointed to is no
+    /// longer part of the chain.
+    ///
+    /// This is meant for handing over a list to foreign code.
+    /// The caller becomes the owner of all boxed elements and is responsible for freeing them,
+    /// e.g. by subtracting [`NtListElement::offset`] from the address of each entry and passing
+    /// the resulting element pointer to [`Box::from_raw`].
+    /// Otherwise, all elements are leaked.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn leak_all(self: Pin<&mut Self>) -> *mut NtListEntry<E, L> {
+        if self.as_ref().is_empty() {
+            return ptr::null_mut();
+        }
+
+        let first = self.0.flink;
+        let last = self.0.blink;
+
+        unsafe {
+            (*first).blink = ptr::null_mut();
+            (*last).flink = ptr::null_mut();
+        }
+
+        self.inner_mut().clear();
+        first
+    }
+
+    /// Counts all elements and returns the length of the list.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn len(self: Pin<&Self>) -> usize {
+        unsafe { self.inner().len() }
+    }
+
+    /// Projects every element into a value of type `T` via `f` and returns all values in a
+    /// [`Vec`], in order.
+    ///
+    /// This is equivalent to `iter().map(f).collect()`.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn map_to_vec<T, F>(self: Pin<&Self>, f: F) -> Vec<T>
+    where
+        F: FnMut(&E) -> T,
+    {
+        self.iter().map(f).collect()
+    }
+
+    /// Projects every element into a value of type `T` via `f` and returns all values in a
+    /// [`Vec`], in order.
+    ///
+    /// Unlike [`map_to_vec`](Self::map_to_vec), `f` gets mutable access to each element, e.g. to
+    /// move data out of it during the projection.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn map_to_vec_mut<T, F>(self: Pin<&mut Self>, f: F) -> Vec<T>
+    where
+        F: FnMut(&mut E) -> T,
+    {
+        self.iter_mut().map(f).collect()
+    }
+
+    /// Moves every element for which `p` returns `true` to the end of `matching` and all other
+    /// elements to the end of `rest`, keeping their order.
+    ///
+    /// The elements are only relinked and not moved in memory.
+    /// After this operation, the list is empty.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `matching` or `rest` is not empty.
+    pub fn partition<P>(
+        mut self: Pin<&mut Self>,
+        mut p: P,
+        mut matching: Pin<&mut Self>,
+        mut rest: Pin<&mut Self>,
+    ) where
+        P: FnMut(&E) -> bool,
+    {
+        assert!(
+            matching.as_ref().is_empty() && rest.as_ref().is_empty(),
+            "destination lists must be empty"
+        );
+
+        let end_marker = self.as_mut().inner_mut().end_marker_mut();
+        let mut current = self.0.flink;
+
+        while current != end_marker {
             unsafe {
-                let entry = NtListHead::entry(Box::leak(element));
+                let next = (*current).flink;
+                let element = NtListEntry::containing_record_mut(current);
 
-                (*entry).flink = end_marker;
-                (*entry).blink = previous;
-                (*previous).flink = entry;
+                // Only unlink the element after `p` has returned, so that all lists stay
+                // consistent if it panics.
+                let is_match = p(element);
+                (*current).remove();
 
-                previous = entry;
+                if is_match {
+                    matching.as_mut().inner_mut().push_back(element);
+                } else {
+                    rest.as_mut().inner_mut().push_back(element);
+                }
+
+                current = next;
             }
         }
+    }
+
+    /// Removes the last element from the list and returns it, or `None` if the list is empty.
+    ///
+    /// This function substitutes [`RemoveTailList`] of the Windows NT API.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    ///
+    /// [`RemoveTailList`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-removetaillist
+    pub fn pop_back(self: Pin<&mut Self>) -> Option<Box<E>> {
+        unsafe {
+            self.inner_mut()
+                .pop_back()
+                .map(|element| Box::from_raw(element))
+        }
+    }
+
+    /// Removes the last element from the list and returns it if the predicate `p` returns
+    /// `true` for it.
+    ///
+    /// Returns `None` if the list is empty or `p` returns `false`, in which case the element
+    /// stays in the list.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn pop_back_if<P>(self: Pin<&mut Self>, p: P) -> Option<Box<E>>
+    where
+        P: FnOnce(&E) -> bool,
+    {
+        if p(self.as_ref().back()?) {
+            self.pop_back()
+        } else {
+            None
+        }
+    }
+
+    /// Removes the first element from the list and returns it, or `None` if the list is empty.
+    ///
+    /// This function substitutes [`RemoveHeadList`] of the Windows NT API.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    ///
+    /// [`RemoveHeadList`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-removeheadlist
+    pub fn pop_front(self: Pin<&mut Self>) -> Option<Box<E>> {
+        unsafe {
+            self.inner_mut()
+                .pop_front()
+                .map(|element| Box::from_raw(element))
+        }
+    }
+
+    /// Removes the first element from the list and returns it if the predicate `p` returns
+    /// `true` for it.
+    ///
+    /// Returns `None` if the list is empty or `p` returns `false`, in which case the element
+    /// stays in the list.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn pop_front_if<P>(self: Pin<&mut Self>, p: P) -> Option<Box<E>>
+    where
+        P: FnOnce(&E) -> bool,
+    {
+        if p(self.as_ref().front()?) {
+            self.pop_front()
+        } else {
+            None
+        }
+    }
+
+    /// Moves the first `n` elements of the list into the empty list `dest`.
+    ///
+    /// If the list has fewer than `n` elements, all of them are moved.
+    /// No element is moved in memory, only the links are updated.
+    ///
+    /// `dest` must be empty, which is checked in debug builds.
+    ///
+    /// This operation computes in *O*(*min*(*n*, *len*)) time.
+    pub fn pop_n_front(mut self: Pin<&mut Self>, n: usize, mut dest: Pin<&mut Self>) {
+        debug_assert!(dest.as_ref().is_empty(), "destination list is not empty");
+
+        if n == 0 || self.as_ref().is_empty() {
+            return;
+        }
+
+        let end_marker = self.as_mut().inner_mut().end_marker_mut();
+        let dest_end_marker = dest.as_mut().inner_mut().end_marker_mut();
+
+        unsafe {
+            // Find the last element to move.
+            let first_moved = self.0.flink;
+            let mut last_moved = first_moved;
+
+            for _ in 1..n {
+                let next = (*last_moved).flink;
+                if next == end_marker {
+                    break;
+                }
+
+                last_moved = next;
+            }
+
+            let first_kept = (*last_moved).flink;
+
+            // Mount the moved elements into `dest`.
+            (*first_moved).blink = dest_end_marker;
+            (*last_moved).flink = dest_end_marker;
+
+            let dest_mut = dest.get_unchecked_mut();
+            dest_mut.0.flink = first_moved;
+            dest_mut.0.blink = last_moved;
+
+            // Let the remaining elements start right after the header of `self`.
+            // If all elements have been moved, `first_kept` is the end marker itself.
+            (*first_kept).blink = end_marker;
+            self.get_unchecked_mut().0.flink = first_kept;
+        }
+    }
+
+    /// Appends an element to the back of the list.
+    ///
+    /// This function substitutes [`InsertTailList`] of the Windows NT API.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    ///
+    /// [`InsertTailList`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-inserttaillist
+    pub fn push_back(self: Pin<&mut Self>, element: E) {
+        let boxed_element = Box::new(element);
+        unsafe { self.inner_mut().push_back(Box::leak(boxed_element)) }
+    }
+
+    /// Appends an element to the front of the list.
+    ///
+    /// This function substitutes [`InsertHeadList`] of the Windows NT API.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    ///
+    /// [`InsertHeadList`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-insertheadlist
+    pub fn push_front(self: Pin<&mut Self>, element: E) {
+        let boxed_element = Box::new(element);
+        unsafe { self.inner_mut().push_front(Box::leak(boxed_element)) }
+    }
+
+    /// Returns the entries of the first and the last element in the given index range.
+    ///
+    /// For an empty range, the entry of the last element is null, and the first entry is the one
+    /// at the start index, which may also be the end marker.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than its end or if the end of the range is
+    /// greater than the length of the list.
+    fn range_entries(
+        mut self: Pin<&mut Self>,
+        range: Range<usize>,
+    ) -> (*mut NtListEntry<E, L>, *mut NtListEntry<E, L>) {
+        let Range { start, end } = range;
+        assert!(
+            start <= end,
+            "range starts at index {} but ends at index {}",
+            start,
+            end
+        );
+
+        let end_marker = self.as_mut().inner_mut().end_marker_mut();
+        let mut current = self.0.flink;
+
+        for i in 0..start {
+            assert!(
+                current != end_marker,
+                "range start index {} out of range for list of length {}",
+                start,
+                i
+            );
+            current = unsafe { (*current).flink };
+        }
+
+        let first = current;
+        let mut last = ptr::null_mut();
+
+        for i in start..end {
+            assert!(
+                current != end_marker,
+                "range end index {} out of range for list of length {}",
+                end,
+                i
+            );
+            last = current;
+            current = unsafe { (*current).flink };
+        }
+
+        (first, last)
+    }
+
+    /// Retains only the elements specified by the predicate, passing a mutable reference to it.
+    ///
+    /// In other words, remove all elements `e` for which `f(&mut e)` returns `false`.
+    /// This method operates in place, visiting each element exactly once in the original order,
+    /// and preserves the order of the retained elements.
+    ///
+    /// If `f` panics, the current element has not been removed yet and stays in the list.
+    /// If the `Drop` handler of a removed element panics, the memory of that element is still
+    /// deallocated, and all other elements stay in the list.
+    ///
+    /// This function substitutes [`RemoveEntryList`] of the Windows NT API.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    ///
+    /// [`RemoveEntryList`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-removeentrylist
+    pub fn retain<F>(self: Pin<&mut Self>, mut f: F)
+    where
+        F: FnMut(&mut E) -> bool,
+    {
+        for element in self.iter_mut() {
+            if !f(element) {
+                let entry = NtListHead::entry(element);
+
+                unsafe {
+                    (*entry).remove();
+                    drop(Box::from_raw(element));
+                }
+            }
+        }
+    }
+
+    /// Retains only the elements specified by the predicate, but removes at most `max_removals`
+    /// elements, and returns the number of removed elements.
+    ///
+    /// This works just like [`retain`](Self::retain), but stops the traversal as soon as
+    /// `max_removals` elements have been removed.
+    /// All remaining elements are kept without being visited, even if they don't satisfy the
+    /// predicate.
+    /// This allows spreading a cleanup over multiple calls, e.g. to bound the work of each call
+    /// in a real-time context.
+    ///
+    /// If `f` panics, the current element has not been removed yet and stays in the list.
+    /// If the `Drop` handler of a removed element panics, the memory of that element is still
+    /// deallocated, and all other elements stay in the list.
+    ///
+    /// This operation computes in *O*(*k*) time, where *k* is the number of visited elements.
+    pub fn retain_budgeted<F>(self: Pin<&mut Self>, max_removals: usize, mut f: F) -> usize
+    where
+        F: FnMut(&mut E) -> bool,
+    {
+        let mut removed = 0;
+
+        self.retain_until(|element| {
+            if removed == max_removals {
+                Decision::StopKeepingRest
+            } else if f(element) {
+                Decision::Keep
+            } else {
+                removed += 1;
+                Decision::Remove
+            }
+        });
+
+        removed
+    }
+
+    /// Retains only the elements specified by the closure, stopping early if requested.
+    ///
+    /// `f` is called for every element in order and decides whether to keep or remove it.
+    /// If it returns [`Decision::StopKeepingRest`], the traversal stops, and the current
+    /// element and all elements after it are kept without being visited.
+    /// This avoids scanning the entire list, e.g. if the list is sorted and all elements past a
+    /// threshold shall be kept.
+    ///
+    /// If `f` panics, the current element has not been removed yet and stays in the list.
+    /// If the `Drop` handler of a removed element panics, the memory of that element is still
+    /// deallocated, and all other elements stay in the list.
+    ///
+    /// This operation computes in *O*(*k*) time, where *k* is the number of visited elements.
+    pub fn retain_until<F>(mut self: Pin<&mut Self>, mut f: F)
+    where
+        F: FnMut(&mut E) -> Decision,
+    {
+        let end_marker = self.as_mut().inner_mut().end_marker_mut();
+        let mut current = self.0.flink;
+
+        while current != end_marker {
+            unsafe {
+                // Store the next pointer before the current element may be deallocated.
+                let next = (*current).flink;
+                let element = NtListEntry::containing_record_mut(current);
+
+                match f(element) {
+                    Decision::Keep => (),
+                    Decision::Remove => {
+                        // Unlink the element before deallocating it, just like `retain` does.
+                        (*current).remove();
+                        drop(Box::from_raw(element));
+                    }
+                    Decision::StopKeepingRest => break,
+                }
+
+                current = next;
+            }
+        }
+    }
+
+    /// Reverses the order of the entries from `first` to `last` (both inclusive) and reconnects
+    /// them to the surrounding entries.
+    unsafe fn reverse_entries(first: *mut NtListEntry<E, L>, last: *mut NtListEntry<E, L>) {
+        let previous = (*first).blink;
+        let after = (*last).flink;
+
+        // Swap the links of every entry in the range.
+        let mut entry = first;
+        loop {
+            let next = (*entry).flink;
+            ptr::swap(&mut (*entry).flink, &mut (*entry).blink);
+
+            if entry == last {
+                break;
+            }
+
+            entry = next;
+        }
+
+        // Reconnect the reversed range to the surrounding entries.
+        (*previous).flink = last;
+        (*last).blink = previous;
+        (*first).flink = after;
+        (*after).blink = first;
+    }
+
+    /// Reverses the order of the elements in every group of `k` consecutive elements.
+    ///
+    /// If the length of the list is not a multiple of `k`, the trailing partial group is only
+    /// reversed if `reverse_remainder` is `true`.
+    /// No element is moved in memory, only the links are updated.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is 0.
+    pub fn reverse_in_groups(mut self: Pin<&mut Self>, k: usize, reverse_remainder: bool) {
+        assert!(k != 0, "group size must not be 0");
+
+        let end_marker = self.as_mut().inner_mut().end_marker_mut();
+        let mut current = self.0.flink;
+
+        while current != end_marker {
+            let first = current;
+            let mut last = current;
+            let mut count = 0;
+
+            while count < k && current != end_marker {
+                last = current;
+                current = unsafe { (*current).flink };
+                count += 1;
+            }
+
+            if count < k && !reverse_remainder {
+                break;
+            }
+
+            // `current` is the first entry of the next group and stays valid, because reversing
+            // only updates its back link.
+            if count >= 2 {
+                unsafe { Self::reverse_entries(first, last) }
+            }
+        }
+    }
+
+    /// Reverses the order of the elements in the given index range.
+    ///
+    /// All other elements stay untouched.
+    /// No element is moved in memory, only the links are updated.
+    ///
+    /// This operation computes in *O*(*end*) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than its end or if its end is greater than
+    /// the length of the list.
+    pub fn reverse_range(self: Pin<&mut Self>, range: Range<usize>) {
+        let count = range.len();
+        let (first, last) = self.range_entries(range);
+
+        if count >= 2 {
+            unsafe { Self::reverse_entries(first, last) }
+        }
+    }
+
+    /// Rotates the list in place such that the given element becomes the first one.
+    ///
+    /// No element is moved in memory.
+    /// Instead, the list header is relinked in front of `element`, keeping the cyclic order of
+    /// all elements intact.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    ///
+    /// # Safety
+    ///
+    /// `element` must be part of this list.
+    /// Passing any other element is undefined behavior, which is checked in debug builds.
+    pub unsafe fn rotate_to(mut self: Pin<&mut Self>, element: &mut E) {
+        let end_marker = self.as_mut().inner_mut().end_marker_mut();
+        let entry = NtListHead::entry(element);
+
+        debug_assert!(
+            self.as_ref().inner().contains_entry(entry),
+            "element is not part of this list"
+        );
+
+        let first = self.0.flink;
+        if first == entry {
+            return;
+        }
+
+        let last = self.0.blink;
+        let previous = (*entry).blink;
+
+        // Close the ring by linking the last element to the first one, thereby taking out the header.
+        (*last).flink = first;
+        (*first).blink = last;
+
+        // Reinsert the header between `previous` and `element`.
+        (*previous).flink = end_marker;
+        (*entry).blink = end_marker;
+
+        let self_mut = self.get_unchecked_mut();
+        self_mut.0.flink = entry;
+        self_mut.0.blink = previous;
+    }
+
+    /// Rotates the list in place such that the first element equal to `value` becomes the first
+    /// one.
+    ///
+    /// Returns `false` and leaves the list unchanged if no element is equal to `value`.
+    /// See [`rotate_to`](Self::rotate_to) for details on the rotation.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn rotate_to_value(mut self: Pin<&mut Self>, value: &E) -> bool
+    where
+        E: PartialEq,
+    {
+        let element = match self.as_mut().iter_mut().find(|element| **element == *value) {
+            Some(element) => element as *mut E,
+            None => return false,
+        };
+
+        // `element` has just been found in this list.
+        unsafe { self.rotate_to(&mut *element) };
+        true
+    }
+
+    /// Searches for the last element matching the predicate and returns its index
+    /// (counted from the front), or `None` if no element matches.
+    ///
+    /// The list is traversed from back to front, so this is faster than a forward search when
+    /// the element is expected near the end of the list.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn rposition<P>(self: Pin<&Self>, mut p: P) -> Option<usize>
+    where
+        P: FnMut(&E) -> bool,
+    {
+        let mut iter = self.iter();
+
+        while let Some(element) = iter.next_back() {
+            if p(element) {
+                // All elements left in the iterator precede the matching one.
+                return Some(iter.count());
+            }
+        }
+
+        None
+    }
+
+    /// Moves all elements from the first one that is greater than or equal to `key` onwards into
+    /// the empty list `dest`.
+    ///
+    /// If the list is sorted, this leaves all elements less than `key` in this list and moves all
+    /// others into `dest`.
+    /// The list is traversed only up to the first element that is not less than `key`, and all
+    /// elements from there on are moved at once.
+    /// No element is moved in memory, only the links are updated.
+    ///
+    /// `dest` must be empty, which is checked in debug builds.
+    ///
+    /// This operation computes in *O*(*k*) time, where *k* is the number of elements less than
+    /// `key`.
+    pub fn split_at_value(mut self: Pin<&mut Self>, key: &E, dest: Pin<&mut Self>)
+    where
+        E: Ord,
+    {
+        debug_assert!(dest.as_ref().is_empty(), "destination list is not empty");
+
+        let end_marker = self.as_mut().inner_mut().end_marker_mut();
+        let first = self.0.flink;
+        let mut current = first;
+
+        unsafe {
+            while current != end_marker {
+                if NtListEntry::containing_record(current) >= key {
+                    break;
+                }
+
+                current = (*current).flink;
+            }
+
+            if current == end_marker {
+                // All elements are less than `key`.
+            } else if current == first {
+                // No element is less than `key`.
+                dest.append(self);
+            } else {
+                let last_kept = NtListEntry::containing_record_mut((*current).blink);
+                self.split_off_after(last_kept, dest);
+            }
+        }
+    }
+
+    /// Removes the first element from the list and returns it, moving all remaining elements
+    /// into the empty list `rest`.
+    ///
+    /// This is analogous to [`slice::split_first`].
+    /// If the list is empty, `None` is returned and both lists stay empty.
+    /// Afterwards, this list is always empty.
+    ///
+    /// `rest` must be empty, which is checked in debug builds.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn split_first(mut self: Pin<&mut Self>, rest: Pin<&mut Self>) -> Option<Box<E>> {
+        debug_assert!(rest.as_ref().is_empty(), "rest list is not empty");
+
+        let first = self.as_mut().pop_front()?;
+        rest.append(self);
+        Some(first)
+    }
+
+    /// Removes the last element from the list and returns it, moving all remaining elements
+    /// into the empty list `rest`.
+    ///
+    /// This is analogous to [`slice::split_last`].
+    /// If the list is empty, `None` is returned and both lists stay empty.
+    /// Afterwards, this list is always empty.
+    ///
+    /// `rest` must be empty, which is checked in debug builds.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn split_last(mut self: Pin<&mut Self>, rest: Pin<&mut Self>) -> Option<Box<E>> {
+        debug_assert!(rest.as_ref().is_empty(), "rest list is not empty");
+
+        let last = self.as_mut().pop_back()?;
+        rest.append(self);
+        Some(last)
+    }
+
+    /// Moves all elements after the given element into the empty list `dest`.
+    ///
+    /// `element` stays in the list and becomes its last element.
+    /// If `element` is already the last element, `dest` remains empty.
+    ///
+    /// `dest` must be empty, which is checked in debug builds.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    ///
+    /// # Safety
+    ///
+    /// `element` must be part of this list.
+    /// Passing any other element is undefined behavior, which is checked in debug builds.
+    pub unsafe fn split_off_after(
+        mut self: Pin<&mut Self>,
+        element: &mut E,
+        mut dest: Pin<&mut Self>,
+    ) {
+        let end_marker = self.as_mut().inner_mut().end_marker_mut();
+        let dest_end_marker = dest.as_mut().inner_mut().end_marker_mut();
+        let entry = NtListHead::entry(element);
+
+        debug_assert!(
+            self.as_ref().inner().contains_entry(entry),
+            "element is not part of this list"
+        );
+        debug_assert!(dest.as_ref().is_empty(), "destination list is not empty");
+
+        let first_moved = (*entry).flink;
+        if first_moved == end_marker {
+            return;
+        }
+
+        let last_moved = self.0.blink;
+
+        // Mount the elements after `element` into `dest`.
+        (*first_moved).blink = dest_end_marker;
+        (*last_moved).flink = dest_end_marker;
+
+        let dest_mut = dest.get_unchecked_mut();
+        dest_mut.0.flink = first_moved;
+        dest_mut.0.blink = last_moved;
+
+        // Make `element` the last element of `self`.
+        (*entry).flink = end_marker;
+        self.get_unchecked_mut().0.blink = entry;
+    }
+
+    /// Swaps the first and the last element of the list by relinking both entries.
+    ///
+    /// The element bodies are not moved in memory.
+    /// If the list contains less than two elements, this is a no-op.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn swap_ends(mut self: Pin<&mut Self>) {
+        let end_marker = self.as_mut().inner_mut().end_marker_mut();
+        let first = self.0.flink;
+        let last = self.0.blink;
+
+        if first == last {
+            // The list is empty or contains a single element.
+            return;
+        }
+
+        unsafe {
+            let second = (*first).flink;
+            if second == last {
+                // Both elements are adjacent.
+                NtListEntry::swap_with_next(first);
+                return;
+            }
+
+            // Turn `first <-> second ... second_last <-> last` into `last <-> second ... second_last <-> first`.
+            let second_last = (*last).blink;
+
+            (*last).blink = end_marker;
+            (*last).flink = second;
+            (*second).blink = last;
+
+            (*second_last).flink = first;
+            (*first).blink = second_last;
+            (*first).flink = end_marker;
+
+            let self_mut = self.get_unchecked_mut();
+            self_mut.0.flink = last;
+            self_mut.0.blink = first;
+        }
+    }
+
+    /// Swaps the given element with its immediate successor by relinking both entries.
+    ///
+    /// The element bodies are not moved in memory.
+    /// If `element` is already the last element of the list, this is a no-op.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    ///
+    /// # Safety
+    ///
+    /// `element` must be part of this list.
+    /// Passing any other element is undefined behavior, which is checked in debug builds.
+    pub unsafe fn swap_with_next(mut self: Pin<&mut Self>, element: &mut E) {
+        let end_marker = self.as_mut().inner_mut().end_marker_mut();
+        let entry = NtListHead::entry(element);
+
+        debug_assert!(
+            self.as_ref().inner().contains_entry(entry),
+            "element is not part of this list"
+        );
+
+        if (*entry).flink != end_marker {
+            NtListEntry::swap_with_next(entry);
+        }
+    }
+
+    /// Returns the number of elements at the front of the list that satisfy the predicate `p`.
+    ///
+    /// Traversal stops at the first element for which `p` returns `false`.
+    /// Combined with [`drain_range`](Self::drain_range) or [`split_off_after`](Self::split_off_after),
+    /// this allows cutting the list at the first element that doesn't satisfy `p`, e.g. to consume
+    /// all elements with a timestamp before a deadline.
+    ///
+    /// This operation computes in *O*(*k*) time, where *k* is the returned count.
+    pub fn take_while_count<P>(self: Pin<&Self>, mut p: P) -> usize
+    where
+        P: FnMut(&E) -> bool,
+    {
+        self.iter().take_while(|element| p(element)).count()
+    }
+
+    /// Collects clones of all elements into a [`BTreeSet`], dropping duplicates.
+    ///
+    /// The order of the list is lost, as the set is ordered by the [`Ord`] implementation of `E`.
+    ///
+    /// This operation computes in *O*(*n* log *n*) time.
+    pub fn to_btree_set(self: Pin<&Self>) -> BTreeSet<E>
+    where
+        E: Clone + Ord,
+    {
+        self.iter().cloned().collect()
+    }
+
+    /// Collects clones of all elements into a [`HashSet`], dropping duplicates.
+    ///
+    /// The order of the list is lost.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn to_hash_set(self: Pin<&Self>) -> HashSet<E>
+    where
+        E: Clone + Eq + Hash,
+    {
+        self.iter().cloned().collect()
+    }
+
+    /// Appends an element to the back of the list, returning it back as `Err` if memory for it
+    /// could not be allocated.
+    ///
+    /// Unlike [`push_back`](Self::push_back), this function never panics or aborts on
+    /// allocation failure.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn try_push_back(self: Pin<&mut Self>, element: E) -> Result<(), E> {
+        let boxed_element = try_box(element)?;
+        unsafe { self.inner_mut().push_back(Box::leak(boxed_element)) }
+        Ok(())
+    }
+
+    /// Appends an element to the front of the list, returning it back as `Err` if memory for it
+    /// could not be allocated.
+    ///
+    /// Unlike [`push_front`](Self::push_front), this function never panics or aborts on
+    /// allocation failure.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn try_push_front(self: Pin<&mut Self>, element: E) -> Result<(), E> {
+        let boxed_element = try_box(element)?;
+        unsafe { self.inner_mut().push_front(Box::leak(boxed_element)) }
+        Ok(())
+    }
+
+    /// Retains only the elements for which `f` returns a replacement, moving every element out of
+    /// the list and passing it by value.
+    ///
+    /// If `f` returns `None`, the element is dropped.
+    /// If `f` returns `Some`, the returned element is boxed anew and put back at the same position.
+    /// This allows transforming and filtering the list in a single pass, visiting each element
+    /// exactly once in the original order.
+    ///
+    /// As every retained element is moved into a new allocation, its address changes.
+    /// Therefore, this function must not be used for elements that are also part of other lists.
+    ///
+    /// If `f` panics, the element passed to it is dropped during unwinding, but all other
+    /// elements stay in the list in their original order.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn update_retain<F>(mut self: Pin<&mut Self>, mut f: F)
+    where
+        F: FnMut(E) -> Option<E>,
+    {
+        let end_marker = self.as_mut().inner_mut().end_marker_mut();
+        let mut current = self.0.flink;
+
+        while current != end_marker {
+            unsafe {
+                // Unlink the element before moving it out of its box, so that the list stays
+                // consistent if `f` panics.
+                let next = (*current).flink;
+                (*current).remove();
+                let element = *Box::from_raw(NtListEntry::containing_record_mut(current));
+
+                if let Some(element) = f(element) {
+                    // Put the replacement where the original element was.
+                    let previous = (*next).blink;
+                    NtListEntry::link_boxed(Box::new(element), previous, next);
+                }
+
+                current = next;
+            }
+        }
+    }
+}
+
+impl<E, L> fmt::Debug for NtBoxingListHead<E, L>
+where
+    E: NtBoxedListElement<L = L> + NtListElement<L> + fmt::Debug,
+    L: NtTypedList<T = NtList>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pinned = unsafe { Pin::new_unchecked(self) };
+        f.debug_list().entries(pinned.iter()).finish()
+    }
+}
+
+impl<E, L> Drop for NtBoxingListHead<E, L>
+where
+    E: NtBoxedListElement<L = L> + NtListElement<L>,
+    L: NtTypedList<T = NtList>,
+{
+    fn drop(&mut self) {
+        let pinned = unsafe { Pin::new_unchecked(self) };
+
+        for element in pinned.iter_mut() {
+            // Reconstruct the `Box` we created in push_back/push_front and let it leave the scope
+            // to call its Drop handler and deallocate the element gracefully.
+            unsafe {
+                drop(Box::from_raw(element));
+            }
+        }
+    }
+}
+
+/// A cursor over a doubly linked list that can insert and remove elements at its position.
+///
+/// The cursor either points to an element or to the "ghost" position between the last and the
+/// first element, which is where it ends up after moving past either end of the list.
+///
+/// This cursor is returned from the [`NtBoxingListHead::cursor_at`] function.
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub struct CursorMut<
+    'a,
+    E: NtBoxedListElement<L = L> + NtListElement<L>,
+    L: NtTypedList<T = NtList>,
+> {
+    end_marker: *mut NtListEntry<E, L>,
+    current: *mut NtListEntry<E, L>,
+    phantom: PhantomData<&'a mut NtBoxingListHead<E, L>>,
+}
+
+impl<'a, E, L> CursorMut<'a, E, L>
+where
+    E: NtBoxedListElement<L = L> + NtListElement<L>,
+    L: NtTypedList<T = NtList>,
+{
+    /// Provides a mutable reference to the element at the cursor position, or `None` if the
+    /// cursor is at the ghost position.
+    pub fn current(&mut self) -> Option<&mut E> {
+        (self.current != self.end_marker)
+            .then(|| unsafe { NtListEntry::containing_record_mut(self.current) })
+    }
+
+    /// Inserts an element after the cursor position.
+    ///
+    /// If the cursor is at the ghost position, the element becomes the first element of the list.
+    /// The cursor position doesn't change.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn insert_after(&mut self, element: E) {
+        unsafe {
+            let next = (*self.current).flink;
+            NtListEntry::link_boxed(Box::new(element), self.current, next);
+        }
+    }
+
+    /// Inserts an element before the cursor position.
+    ///
+    /// If the cursor is at the ghost position, the element becomes the last element of the list.
+    /// The cursor position doesn't change.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn insert_before(&mut self, element: E) {
+        unsafe {
+            let previous = (*self.current).blink;
+            NtListEntry::link_boxed(Box::new(element), previous, self.current);
+        }
+    }
+
+    /// Moves the cursor to the next element.
+    ///
+    /// If the cursor is at the last element, it moves to the ghost position.
+    /// If it is at the ghost position, it moves to the first element.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn move_next(&mut self) {
+        self.current = unsafe { (*self.current).flink };
+    }
+
+    /// Moves the cursor to the previous element.
+    ///
+    /// If the cursor is at the first element, it moves to the ghost position.
+    /// If it is at the ghost position, it moves to the last element.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn move_prev(&mut self) {
+        self.current = unsafe { (*self.current).blink };
+    }
+
+    /// Removes the element at the cursor position and returns it, or `None` if the cursor is at
+    /// the ghost position.
+    ///
+    /// The cursor moves to the next element afterwards.
+    ///
+    /// This function substitutes [`RemoveEntryList`] of the Windows NT API.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    ///
+    /// [`RemoveEntryList`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-removeentrylist
+    pub fn remove_current(&mut self) -> Option<Box<E>> {
+        if self.current == self.end_marker {
+            return None;
+        }
+
+        unsafe {
+            let entry = self.current;
+            self.current = (*entry).flink;
+            (*entry).remove();
+
+            Some(Box::from_raw(NtListEntry::containing_record_mut(entry)))
+        }
+    }
+}
+
+/// Decision returned by the closure passed to the `retain_until` functions of the boxing list
+/// heads.
+///
+/// See [`NtBoxingListHead::retain_until`] and [`NtBoxingSingleListHead::retain_until`].
+///
+/// [`NtBoxingSingleListHead::retain_until`]: crate::single_list::NtBoxingSingleListHead::retain_until
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Decision {
+    /// Keep the element in the list.
+    Keep,
+    /// Remove the element from the list and drop it.
+    Remove,
+    /// Keep the element and all elements after it in the list without visiting them.
+    StopKeepingRest,
+}
+
+/// Iterator over the elements removed from a doubly linked list via
+/// [`NtBoxingListHead::drain_range`].
+///
+/// The elements have already been unlinked from the list.
+/// Dropping this iterator deallocates all elements that have not been yielded yet.
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub struct DrainRange<E: NtBoxedListElement<L = L> + NtListElement<L>, L: NtTypedList<T = NtList>> {
+    front: *mut NtListEntry<E, L>,
+    back: *mut NtListEntry<E, L>,
+    remaining: usize,
+}
+
+impl<E, L> Iterator for DrainRange<E, L>
+where
+    E: NtBoxedListElement<L = L> + NtListElement<L>,
+    L: NtTypedList<T = NtList>,
+{
+    type Item = Box<E>;
+
+    fn next(&mut self) -> Option<Box<E>> {
+        if self.remaining == 0 {
+            None
+        } else {
+            unsafe {
+                let entry = self.front;
+                self.front = (*entry).flink;
+                self.remaining -= 1;
+                Some(Box::from_raw(NtListEntry::containing_record_mut(entry)))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<E, L> DoubleEndedIterator for DrainRange<E, L>
+where
+    E: NtBoxedListElement<L = L> + NtListElement<L>,
+    L: NtTypedList<T = NtList>,
+{
+    fn next_back(&mut self) -> Option<Box<E>> {
+        if self.remaining == 0 {
+            None
+        } else {
+            unsafe {
+                let entry = self.back;
+                self.back = (*entry).blink;
+                self.remaining -= 1;
+                Some(Box::from_raw(NtListEntry::containing_record_mut(entry)))
+            }
+        }
+    }
+}
+
+impl<E, L> ExactSizeIterator for DrainRange<E, L>
+where
+    E: NtBoxedListElement<L = L> + NtListElement<L>,
+    L: NtTypedList<T = NtList>,
+{
+}
+
+impl<E, L> FusedIterator for DrainRange<E, L>
+where
+    E: NtBoxedListElement<L = L> + NtListElement<L>,
+    L: NtTypedList<T = NtList>,
+{
+}
+
+impl<E, L> Drop for DrainRange<E, L>
+where
+    E: NtBoxedListElement<L = L> + NtListElement<L>,
+    L: NtTypedList<T = NtList>,
+{
+    fn drop(&mut self) {
+        for element in self {
+            drop(element);
+        }
+    }
+}
+
+/// An element of a doubly linked list passed to the closure of
+/// [`NtBoxingListHead::for_each_with_cursor`].
+///
+/// Dropping it without calling [`remove`](Self::remove) keeps the element in the list.
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub struct ElementCursor<
+    'a,
+    E: NtBoxedListElement<L = L> + NtListElement<L>,
+    L: NtTypedList<T = NtList>,
+> {
+    entry: *mut NtListEntry<E, L>,
+    insert_position: *mut NtListEntry<E, L>,
+    phantom: PhantomData<&'a mut NtBoxingListHead<E, L>>,
+}
+
+impl<'a, E, L> ElementCursor<'a, E, L>
+where
+    E: NtBoxedListElement<L = L> + NtListElement<L>,
+    L: NtTypedList<T = NtList>,
+{
+    /// Provides a mutable reference to the element.
+    pub fn element(&mut self) -> &mut E {
+        unsafe { NtListEntry::containing_record_mut(self.entry) }
+    }
+
+    /// Inserts an element after the current one.
+    ///
+    /// Elements inserted via multiple calls keep the order of these calls.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn insert_after(&mut self, element: E) {
+        unsafe {
+            let previous = self.insert_position;
+            let next = (*previous).flink;
+            self.insert_position = NtListEntry::link_boxed(Box::new(element), previous, next);
+        }
+    }
+
+    /// Inserts an element before the current one.
+    ///
+    /// Elements inserted via multiple calls keep the order of these calls.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn insert_before(&mut self, element: E) {
+        unsafe {
+            let previous = (*self.entry).blink;
+            NtListEntry::link_boxed(Box::new(element), previous, self.entry);
+        }
+    }
+
+    /// Removes the element from the list and returns it.
+    ///
+    /// Elements inserted before via this [`ElementCursor`] stay in the list.
+    ///
+    /// This function substitutes [`RemoveEntryList`] of the Windows NT API.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    ///
+    /// [`RemoveEntryList`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-removeentrylist
+    pub fn remove(self) -> Box<E> {
+        unsafe {
+            (*self.entry).remove();
+            Box::from_raw(NtListEntry::containing_record_mut(self.entry))
+        }
+    }
+}
+
+/// Iterator over the elements of a doubly linked list, allowing to remove each of them.
+///
+/// This iterator is returned from the [`NtBoxingListHead::iter_with_remover`] function.
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub struct IterWithRemover<
+    'a,
+    E: NtBoxedListElement<L = L> + NtListElement<L>,
+    L: NtTypedList<T = NtList>,
+> {
+    end_marker: *mut NtListEntry<E, L>,
+    current: *mut NtListEntry<E, L>,
+    phantom: PhantomData<&'a mut NtBoxingListHead<E, L>>,
+}
+
+impl<'a, E, L> Iterator for IterWithRemover<'a, E, L>
+where
+    E: NtBoxedListElement<L = L> + NtListElement<L>,
+    L: NtTypedList<T = NtList>,
+{
+    type Item = RemovableElement<'a, E, L>;
+
+    fn next(&mut self) -> Option<RemovableElement<'a, E, L>> {
+        if self.current == self.end_marker {
+            None
+        } else {
+            // Advance before handing out the element.
+            // Removing any element that has already been handed out only modifies the links of
+            // its neighbors, so the next element stays valid.
+            let entry = self.current;
+            self.current = unsafe { (*entry).flink };
+
+            Some(RemovableElement {
+                entry,
+                phantom: PhantomData,
+            })
+        }
+    }
+}
+
+impl<'a, E, L> FusedIterator for IterWithRemover<'a, E, L>
+where
+    E: NtBoxedListElement<L = L> + NtListElement<L>,
+    L: NtTypedList<T = NtList>,
+{
+}
+
+/// An element of a doubly linked list yielded by [`IterWithRemover`].
+///
+/// Dropping it without calling [`remove`](Self::remove) keeps the element in the list.
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub struct RemovableElement<
+    'a,
+    E: NtBoxedListElement<L = L> + NtListElement<L>,
+    L: NtTypedList<T = NtList>,
+> {
+    entry: *mut NtListEntry<E, L>,
+    phantom: PhantomData<&'a mut NtBoxingListHead<E, L>>,
+}
+
+impl<'a, E, L> RemovableElement<'a, E, L>
+where
+    E: NtBoxedListElement<L = L> + NtListElement<L>,
+    L: NtTypedList<T = NtList>,
+{
+    /// Provides a mutable reference to the element.
+    pub fn element(&mut self) -> &mut E {
+        unsafe { NtListEntry::containing_record_mut(self.entry) }
+    }
+
+    /// Removes the element from the list and returns it.
+    ///
+    /// This function substitutes [`RemoveEntryList`] of the Windows NT API.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    ///
+    /// [`RemoveEntryList`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-removeentrylist
+    pub fn remove(self) -> Box<E> {
+        unsafe {
+            (*self.entry).remove();
+            Box::from_raw(NtListEntry::containing_record_mut(self.entry))
+        }
+    }
+}
+
+/// Moves all elements of all `lists` to the end of `dest`, in order.
+///
+/// This calls [`NtBoxingListHead::append`] for every list, so each list is appended in *O*(*1*)
+/// time without traversing its elements.
+/// After this operation, all `lists` are empty.
+///
+/// This operation computes in *O*(*m*) time, where *m* is the number of lists.
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn concat<'a, E, L, I>(lists: I, mut dest: Pin<&mut NtBoxingListHead<E, L>>)
+where
+    E: NtBoxedListElement<L = L> + NtListElement<L> + 'a,
+    L: NtTypedList<T = NtList> + 'a,
+    I: IntoIterator<Item = Pin<&'a mut NtBoxingListHead<E, L>>>,
+{
+    for list in lists {
+        dest.as_mut().append(list);
+    }
+}
+
+/// Removes `element` from the [`NtBoxingListHead`] it is part of and returns it.
+///
+/// This allows an element to detach itself without having access to the list header, e.g. in
+/// response to an event.
+/// The list is identified via the list type of the `#[boxed]` entry of `element`.
+///
+/// This function substitutes [`RemoveEntryList`] of the Windows NT API.
+///
+/// This operation computes in *O*(*1*) time.
+///
+/// # Safety
+///
+/// `element` must be part of an [`NtBoxingListHead`], because only then it has been boxed and
+/// ownership can be returned to the caller.
+/// Passing an element that is not part of any list or that has been pushed to an [`NtListHead`]
+/// is undefined behavior.
+/// The former is checked in debug builds.
+///
+/// [`RemoveEntryList`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-removeentrylist
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub unsafe fn unlink_boxed<E, L>(element: &mut E) -> Box<E>
+where
+    E: NtBoxedListElement<L = L> + NtListElement<L>,
+    L: NtTypedList<T = NtList>,
+{
+    let entry = NtListHead::entry(element);
+
+    debug_assert!(
+        !(*entry).flink.is_null()
+            && (*(*entry).flink).blink == entry
+            && (*(*entry).blink).flink == entry,
+        "element is not part of a list"
+    );
+
+    (*entry).remove();
+    Box::from_raw(NtListEntry::containing_record_mut(entry))
+}
+
+impl<E, L> Extend<Box<E>> for Pin<&mut NtBoxingListHead<E, L>>
+where
+    E: NtBoxedListElement<L = L> + NtListElement<L>,
+    L: NtTypedList<T = NtList>,
+{
+    fn extend<T>(&mut self, iter: T)
+    where
+        T: IntoIterator<Item = Box<E>>,
+    {
+        let end_marker = self.as_mut().inner_mut().end_marker_mut();
+        let mut previous = self.as_ref().inner().blink;
+
+        for element in iter.into_iter() {
+            // We could use `NtBoxingListHead::push_back` here, but this manual implementation
+            // is slightly optimized (doesn't modify list head's `blink` on every iteration).
+            unsafe {
+                let entry = NtListHead::entry(Box::leak(element));
+
+                (*entry).flink = end_marker;
+                (*entry).blink = previous;
+                (*previous).flink = entry;
+
+                previous = entry;
+            }
+        }
+
+        unsafe {
+            self.as_mut().get_unchecked_mut().0.blink = previous;
+        }
+    }
+}
+
+impl<E, L> Extend<E> for Pin<&mut NtBoxingListHead<E, L>>
+where
+    E: NtBoxedListElement<L = L> + NtListElement<L>,
+    L: NtTypedList<T = NtList>,
+{
+    fn extend<T>(&mut self, iter: T)
+    where
+        T: IntoIterator<Item = E>,
+    {
+        self.extend(iter.into_iter().map(Box::new))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::list::NtListEntry;
+    use alloc::format;
+    use alloc::vec::Vec;
+    use core::mem::MaybeUninit;
+    use moveit::moveit;
+
+    #[derive(Debug, NtList)]
+    enum MyList {}
+
+    #[derive(Debug, Default, NtListElement)]
+    #[repr(C)]
+    struct MyElement {
+        value: i32,
+        #[boxed]
+        entry: NtListEntry<Self, MyList>,
+    }
+
+    impl MyElement {
+        fn new(value: i32) -> Self {
+            Self {
+                value,
+                ..Default::default()
+            }
+        }
+    }
+
+    impl Clone for MyElement {
+        fn clone(&self) -> Self {
+            Self::new(self.value)
+        }
+    }
+
+    impl PartialEq for MyElement {
+        fn eq(&self, other: &Self) -> bool {
+            self.value == other.value
+        }
+    }
+
+    impl Eq for MyElement {}
+
+    impl Ord for MyElement {
+        fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+            self.value.cmp(&other.value)
+        }
+    }
+
+    impl PartialOrd for MyElement {
+        fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl core::hash::Hash for MyElement {
+        fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+            self.value.hash(state);
+        }
+    }
+
+    #[test]
+    fn test_append() {
+        // Append two lists of equal size.
+        moveit! {
+            let mut list1 = NtBoxingListHead::<MyElement, MyList>::new();
+            let mut list2 = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..10 {
+            list1.as_mut().push_back(MyElement::new(i));
+            list2.as_mut().push_back(MyElement::new(i));
+        }
+
+        list1.as_mut().append(list2.as_mut());
+
+        assert_eq!(list1.as_ref().len(), 20);
+        assert_eq!(list2.as_ref().len(), 0);
+
+        for (i, element) in (0..10).chain(0..10).zip(list1.as_ref().iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        verify_all_links(list1.as_ref().inner());
+
+        // Append the final list to an empty list.
+        moveit! {
+            let mut list3 = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        list3.as_mut().append(list1.as_mut());
+
+        assert_eq!(list3.as_ref().len(), 20);
+        assert_eq!(list1.as_ref().len(), 0);
+
+        verify_all_links(list3.as_ref().inner());
+    }
+
+    #[test]
+    fn test_append_counted() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+            let mut other = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        assert_eq!(list.as_mut().append_counted(other.as_mut()), 0);
+
+        for i in 0..5 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        for i in 5..8 {
+            other.as_mut().push_back(MyElement::new(i));
+        }
+
+        assert_eq!(list.as_mut().append_counted(other.as_mut()), 3);
+        assert!(other.as_ref().is_empty());
+        assert_eq!(list.as_ref().len(), 8);
+
+        for (i, element) in (0..8).zip(list.as_ref().iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        verify_all_links(list.as_ref().inner());
+        verify_all_links(other.as_ref().inner());
+    }
+
+    #[test]
+    fn test_append_from() {
+        moveit! {
+            let mut list1 = NtBoxingListHead::<MyElement, MyList>::new();
+            let mut list2 = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..5 {
+            list1.as_mut().push_back(MyElement::new(i));
+        }
+        for i in 5..10 {
+            list2.as_mut().push_back(MyElement::new(i));
+        }
+
+        list1
+            .as_mut()
+            .append_from(core::iter::from_fn(|| list2.as_mut().pop_front()));
+
+        assert_eq!(list1.as_ref().len(), 10);
+        assert!(list2.as_ref().is_empty());
+
+        for (i, element) in (0..10).zip(list1.as_ref().iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        verify_all_links(list1.as_ref().inner());
+        verify_all_links(list2.as_ref().inner());
+    }
+
+    #[test]
+    fn test_as_vec() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..5 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        let mut elements = list.as_mut().as_mut_vec();
+        assert_eq!(elements.len(), 5);
+
+        for i in 0..5 {
+            elements[4 - i].value *= 10;
+        }
+
+        let elements = list.as_ref().as_ref_vec();
+        assert_eq!(elements.len(), 5);
+        assert_eq!(elements[3].value, 30);
+
+        for (i, element) in (0..50).step_by(10).zip(elements) {
+            assert_eq!(i, element.value);
+        }
+    }
+
+    #[test]
+    fn test_clear_after_panic() {
+        extern crate std;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        #[derive(NtListElement)]
+        #[repr(C)]
+        struct PanickingElement {
+            value: i32,
+            #[boxed]
+            entry: NtListEntry<Self, MyList>,
+        }
+
+        impl Drop for PanickingElement {
+            fn drop(&mut self) {
+                if self.value == 2 {
+                    panic!("dropping element 2");
+                }
+            }
+        }
+
+        moveit! {
+            let mut list = NtBoxingListHead::<PanickingElement, MyList>::new();
+        }
+
+        for value in 0..5 {
+            list.as_mut().push_back(PanickingElement {
+                value,
+                entry: NtListEntry::new(),
+            });
+        }
+
+        let result = catch_unwind(AssertUnwindSafe(|| list.as_mut().clear()));
+        assert!(result.is_err());
+
+        // The elements after the panicking one must still be in the list.
+        assert_eq!(list.as_ref().len(), 2);
+        assert_eq!(list.as_ref().front().unwrap().value, 3);
+        assert_eq!(list.as_ref().back().unwrap().value, 4);
+
+        // Clearing again drops the remaining elements.
+        list.as_mut().clear();
+        assert!(list.as_ref().is_empty());
+    }
+
+    #[test]
+    fn test_clear_and_append() {
+        // Append two lists of equal size.
+        moveit! {
+            let mut list1 = NtBoxingListHead::<MyElement, MyList>::new();
+            let mut list2 = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..10 {
+            list1.as_mut().push_back(MyElement::new(i));
+            list2.as_mut().push_back(MyElement::new(i));
+        }
+
+        list1.as_mut().append(list2.as_mut());
+
+        assert_eq!(list1.as_ref().len(), 20);
+        assert_eq!(list2.as_ref().len(), 0);
+
+        for (i, element) in (0..10).chain(0..10).zip(list1.as_ref().iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        verify_all_links(list1.as_ref().inner());
+
+        // Add more elements to both lists
+        list1.as_mut().push_back(MyElement::new(21));
+        list1.as_mut().push_front(MyElement::new(22));
+
+        list2.as_mut().push_back(MyElement::new(21));
+        list2.as_mut().push_front(MyElement::new(22));
+
+        // Append the final list to a cleared list.
+        moveit! {
+            let mut list3 = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        list3.as_mut().clear();
+        list3.as_mut().append(list1.as_mut());
+
+        assert_eq!(list3.as_ref().len(), 22);
+        assert_eq!(list1.as_ref().len(), 0);
+
+        verify_all_links(list3.as_ref().inner());
+    }
+
+    #[test]
+    fn test_clear_and_push() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        list.as_mut().clear();
+
+        for i in 0..=3 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+        for i in 4..=6 {
+            list.as_mut().push_front(MyElement::new(i));
+        }
+
+        assert_eq!(list.as_ref().back().unwrap().value, 3);
+        assert_eq!(list.as_mut().back_mut().unwrap().value, 3);
+        assert_eq!(list.as_ref().front().unwrap().value, 6);
+        assert_eq!(list.as_mut().front_mut().unwrap().value, 6);
+
+        verify_all_links(list.as_ref().inner());
+    }
+
+    #[test]
+    fn test_clear_with() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..5 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        let mut values = Vec::new();
+        list.as_mut()
+            .clear_with(|element| values.push(element.value));
+
+        assert_eq!(values, [0, 1, 2, 3, 4]);
+        assert!(list.as_ref().is_empty());
+        verify_all_links(list.as_ref().inner());
+    }
+
+    #[test]
+    fn test_back_and_front() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..=3 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        assert_eq!(list.as_ref().back().unwrap().value, 3);
+        assert_eq!(list.as_mut().back_mut().unwrap().value, 3);
+        assert_eq!(list.as_ref().front().unwrap().value, 0);
+        assert_eq!(list.as_mut().front_mut().unwrap().value, 0);
+    }
+
+    #[test]
+    fn test_clone_into_new() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..10 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        moveit! {
+            let mut cloned = list.as_ref().clone_into_new();
+        }
+
+        assert_eq!(cloned.as_ref().len(), 10);
+        verify_all_links(cloned.as_ref().inner());
+
+        for (element, cloned_element) in list.as_ref().iter().zip(cloned.as_ref().iter()) {
+            assert_eq!(element.value, cloned_element.value);
+            assert!(!ptr::eq(element, cloned_element));
+        }
+
+        // Modifying and dropping the original list must not affect the clone.
+        list.as_mut().front_mut().unwrap().value = 42;
+        list.as_mut().clear();
+
+        assert_eq!(cloned.as_ref().front().unwrap().value, 0);
+        cloned.as_mut().push_back(MyElement::new(10));
+
+        for (i, element) in (0..=10).zip(cloned.as_ref().iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        verify_all_links(cloned.as_ref().inner());
+    }
+
+    #[test]
+    fn test_cloned() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..5 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        let mut clones = list.as_ref().cloned();
+
+        for i in 0..5 {
+            let clone = clones.next().unwrap();
+            assert_eq!(i, clone.value);
+            assert!(!list.as_ref().contains_ptr(&clone));
+        }
+
+        assert!(clones.next().is_none());
+    }
+
+    #[test]
+    fn test_collect_entries() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..5 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        let expected = list
+            .as_ref()
+            .iter()
+            .map(|element| &element.entry as *const NtListEntry<MyElement, MyList>)
+            .collect::<Vec<_>>();
+
+        // A buffer that is large enough receives all entries.
+        let mut buffer = [ptr::null(); 8];
+        assert_eq!(list.as_ref().collect_entries(&mut buffer), 5);
+        assert_eq!(&buffer[..5], &expected[..]);
+        assert!(buffer[5..].iter().all(|entry| entry.is_null()));
+
+        // A buffer that is too small receives the first entries.
+        let mut buffer = [ptr::null(); 3];
+        assert_eq!(list.as_ref().collect_entries(&mut buffer), 3);
+        assert_eq!(&buffer[..], &expected[..3]);
+    }
+
+    #[test]
+    fn test_collect_into() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..3 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        let mut buf = Vec::with_capacity(10);
+        buf.push(MyElement::new(-1));
+        list.as_ref().collect_into(&mut buf);
+        assert!(buf.iter().map(|element| element.value).eq([-1, 0, 1, 2]));
+
+        // The capacity is reused across calls.
+        let capacity = buf.capacity();
+        buf.clear();
+        list.as_ref().collect_into(&mut buf);
+        assert!(buf.iter().map(|element| element.value).eq([0, 1, 2]));
+        assert_eq!(buf.capacity(), capacity);
+
+        let mut refs = Vec::new();
+        list.as_ref().collect_refs_into(&mut refs);
+        assert_eq!(refs.len(), 3);
+
+        for (element, list_element) in refs.iter().zip(list.as_ref().iter()) {
+            assert!(ptr::eq(*element, list_element));
+        }
+    }
+
+    #[test]
+    fn test_concat() {
+        moveit! {
+            let mut list1 = NtBoxingListHead::<MyElement, MyList>::new();
+            let mut list2 = NtBoxingListHead::<MyElement, MyList>::new();
+            let mut list3 = NtBoxingListHead::<MyElement, MyList>::new();
+            let mut dest = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        dest.as_mut().push_back(MyElement::new(0));
+
+        for i in 1..4 {
+            list1.as_mut().push_back(MyElement::new(i));
+        }
+
+        for i in 4..6 {
+            list3.as_mut().push_back(MyElement::new(i));
+        }
+
+        concat(
+            [list1.as_mut(), list2.as_mut(), list3.as_mut()],
+            dest.as_mut(),
+        );
+
+        assert!(list1.as_ref().is_empty());
+        assert!(list2.as_ref().is_empty());
+        assert!(list3.as_ref().is_empty());
+        assert_eq!(dest.as_ref().len(), 6);
+
+        for (i, element) in (0..6).zip(dest.as_ref().iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        verify_all_links(dest.as_ref().inner());
+    }
+
+    #[test]
+    fn test_contains_ptr() {
+        moveit! {
+            let mut list1 = NtBoxingListHead::<MyElement, MyList>::new();
+            let mut list2 = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        assert!(!list1.as_ref().contains_ptr(ptr::null()));
+
+        for i in 0..5 {
+            list1.as_mut().push_back(MyElement::new(i));
+            list2.as_mut().push_back(MyElement::new(i));
+        }
+
+        for element in list1.as_ref().iter() {
+            assert!(list1.as_ref().contains_ptr(element));
+            assert!(!list2.as_ref().contains_ptr(element));
+        }
+
+        // An equal value is not the same element.
+        let other = MyElement::new(0);
+        assert!(!list1.as_ref().contains_ptr(&other));
+    }
+
+    #[test]
+    fn test_cursor_at() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..5 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        // Locate an element by reference first, just like an external index would.
+        let element = list
+            .as_mut()
+            .find_mut(|element| element.value == 2)
+            .unwrap() as *mut MyElement;
+
+        let mut cursor = unsafe { list.as_mut().cursor_at(&mut *element) };
+        assert_eq!(cursor.current().unwrap().value, 2);
+
+        cursor.insert_before(MyElement::new(10));
+        cursor.insert_after(MyElement::new(11));
+        assert_eq!(cursor.current().unwrap().value, 2);
+
+        assert_eq!(cursor.remove_current().unwrap().value, 2);
+        assert_eq!(cursor.current().unwrap().value, 11);
+
+        cursor.move_prev();
+        assert_eq!(cursor.current().unwrap().value, 10);
+
+        // Move past the last element to the ghost position and insert at both ends.
+        cursor.move_next();
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.current().map(|element| element.value), Some(4));
+        cursor.move_next();
+        assert!(cursor.current().is_none());
+        assert!(cursor.remove_current().is_none());
+
+        cursor.insert_after(MyElement::new(-1));
+        cursor.insert_before(MyElement::new(5));
+
+        cursor.move_next();
+        assert_eq!(cursor.current().unwrap().value, -1);
+
+        for (i, element) in [-1, 0, 1, 10, 11, 3, 4, 5]
+            .into_iter()
+            .zip(list.as_ref().iter())
+        {
+            assert_eq!(i, element.value);
+        }
+
+        assert_eq!(list.as_ref().len(), 8);
+        verify_all_links(list.as_ref().inner());
+    }
+
+    #[test]
+    fn test_debug() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        assert_eq!(format!("{:?}", list.as_ref()), "[]");
+
+        for i in 0..2 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        let output = format!("{:?}", list.as_ref());
+        assert!(output.starts_with("[MyElement { value: 0, "));
+        assert!(output.contains("MyElement { value: 1, "));
+    }
+
+    #[test]
+    fn test_dedup_keep_last_by() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        // Use the tens as the key and the ones to tell the elements of a run apart.
+        for i in [0, 1, 2, 10, 20, 21, 30, 31, 32] {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        list.as_mut()
+            .dedup_keep_last_by(|a, b| a.value / 10 == b.value / 10);
+
+        assert_eq!(list.as_ref().len(), 4);
+
+        for (i, element) in [2, 10, 21, 32].into_iter().zip(list.as_ref().iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        verify_all_links(list.as_ref().inner());
+    }
+
+    #[test]
+    fn test_distance() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..10 {
+            list.as_mut().push_back(MyElement::new(i % 5));
+        }
+
+        assert_eq!(
+            list.as_ref().distance_to(|element| element.value == 3),
+            Some(3)
+        );
+        assert_eq!(
+            list.as_ref().distance_back_to(|element| element.value == 3),
+            Some(1)
+        );
+        assert_eq!(
+            list.as_ref().distance_back_to(|element| element.value == 4),
+            Some(0)
+        );
+        assert_eq!(
+            list.as_ref().distance_to(|element| element.value == 5),
+            None
+        );
+        assert_eq!(
+            list.as_ref().distance_back_to(|element| element.value == 5),
+            None
+        );
+    }
+
+    #[test]
+    fn test_drain_collect() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        assert!(list.as_mut().drain_collect().is_empty());
+
+        for i in 0..5 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        let elements = list.as_mut().drain_collect();
+        assert_eq!(elements.len(), 5);
+        assert_eq!(elements.capacity(), 5);
+        assert!(list.as_ref().is_empty());
+        verify_all_links(list.as_ref().inner());
+
+        for (i, element) in (0..5).zip(elements) {
+            assert_eq!(i, element.value);
+        }
+    }
+
+    #[test]
+    fn test_drain_range() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..10 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        // Drain a range in the middle.
+        let drained = list.as_mut().drain_range(3..6);
+        assert_eq!(drained.len(), 3);
+
+        for (i, element) in (3..6).zip(drained) {
+            assert_eq!(i, element.value);
+        }
+
+        for (i, element) in [0, 1, 2, 6, 7, 8, 9].into_iter().zip(list.as_ref().iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        verify_all_links(list.as_ref().inner());
+
+        // Drain an empty range.
+        assert_eq!(list.as_mut().drain_range(7..7).count(), 0);
+        assert_eq!(list.as_ref().len(), 7);
+
+        // Drain the last elements from the back and drop the iterator early.
+        let mut drained = list.as_mut().drain_range(4..7);
+        assert_eq!(drained.next_back().unwrap().value, 9);
+        drop(drained);
+
+        for (i, element) in [0, 1, 2, 6].into_iter().zip(list.as_ref().iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        assert_eq!(list.as_ref().len(), 4);
+        verify_all_links(list.as_ref().inner());
+
+        // Drain everything.
+        assert_eq!(list.as_mut().drain_range(0..4).count(), 4);
+        assert!(list.as_ref().is_empty());
+        verify_all_links(list.as_ref().inner());
+    }
+
+    #[test]
+    #[should_panic(expected = "range end index 4 out of range for list of length 3")]
+    fn test_drain_range_out_of_bounds() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..3 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        list.as_mut().drain_range(1..4);
+    }
+
+    #[test]
+    #[should_panic(expected = "range start index 5 out of range for list of length 3")]
+    fn test_drain_range_start_out_of_bounds() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..3 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        list.as_mut().drain_range(5..6);
+    }
+
+    #[test]
+    fn test_drain_while() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in [1, 2, 3, 10, 4, 5] {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        // Dropping the iterator early must only remove the elements that have been yielded.
+        let mut drain = list.as_mut().drain_while(|element| element.value < 5);
+        assert_eq!(drain.next().unwrap().value, 1);
+        drop(drain);
+
+        assert_eq!(list.as_ref().len(), 5);
+        assert_eq!(list.as_ref().front().unwrap().value, 2);
+
+        let drained = list
+            .as_mut()
+            .drain_while(|element| element.value < 5)
+            .map(|element| element.value);
+        assert!(drained.eq([2, 3]));
+
+        // The first failing element stays in the list.
+        assert_eq!(list.as_ref().len(), 3);
+        assert_eq!(list.as_ref().front().unwrap().value, 10);
+        assert!(list
+            .as_mut()
+            .drain_while(|element| element.value < 5)
+            .next()
+            .is_none());
+        verify_all_links(list.as_ref().inner());
+
+        // The predicate must not be called again after it has returned `false`.
+        list.as_mut().push_front(MyElement::new(0));
+        let mut calls = 0;
+        let mut drain = list.as_mut().drain_while(|element| {
+            calls += 1;
+            element.value < 5
+        });
+        assert_eq!(drain.next().unwrap().value, 0);
+        assert!(drain.next().is_none());
+        assert!(drain.next().is_none());
+        assert!(drain.next().is_none());
+        drop(drain);
+
+        assert_eq!(calls, 2);
+        assert_eq!(list.as_ref().len(), 3);
+    }
+
+    #[test]
+    fn test_element_mut_from_entry() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..5 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        let mut entries = [ptr::null(); 5];
+        assert_eq!(list.as_ref().collect_entries(&mut entries), 5);
+
+        for (i, entry) in (0..5).zip(entries) {
+            let element = unsafe { list.as_mut().element_mut_from_entry(entry as *mut _) };
+            assert_eq!(i, element.value);
+            element.value *= 10;
+        }
+
+        for (i, element) in (0..50).step_by(10).zip(list.as_ref().iter()) {
+            assert_eq!(i, element.value);
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "entry is not part of this list")]
+    fn test_element_mut_from_entry_foreign() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        list.as_mut().push_back(MyElement::new(0));
+
+        let mut element = MyElement::new(1);
+        let entry = NtListHead::entry(&mut element);
+        unsafe { list.as_mut().element_mut_from_entry(entry) };
+    }
+
+    #[test]
+    fn test_entries() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        assert!(list.as_ref().front_entry().is_none());
+        assert!(list.as_ref().back_entry().is_none());
+        assert!(list.as_ref().front_with_entry().is_none());
+        assert!(list.as_ref().back_with_entry().is_none());
+
+        for i in 0..5 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        let mut entries = [ptr::null(); 5];
+        list.as_ref().collect_entries(&mut entries);
+
+        assert_eq!(list.as_ref().front_entry(), Some(entries[0]));
+        assert_eq!(list.as_ref().back_entry(), Some(entries[4]));
+
+        let (front, front_entry) = list.as_ref().front_with_entry().unwrap();
+        assert_eq!(front.value, 0);
+        assert_eq!(front_entry, entries[0]);
+
+        let (back, back_entry) = list.as_ref().back_with_entry().unwrap();
+        assert_eq!(back.value, 4);
+        assert_eq!(back_entry, entries[4]);
+
+        for (element, entry) in list.as_ref().iter().zip(entries) {
+            assert_eq!(NtBoxingListHead::entry_of(element), entry);
+        }
+    }
+
+    #[test]
+    fn test_eq_by() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+            let mut other = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        let same_parity = |a: &MyElement, b: &MyElement| a.value % 2 == b.value % 2;
+        assert!(list.as_ref().eq_by(other.as_ref(), same_parity));
+
+        for i in 0..5 {
+            list.as_mut().push_back(MyElement::new(i));
+            other.as_mut().push_back(MyElement::new(i + 2));
+        }
+
+        assert!(list.as_ref().eq_by(other.as_ref(), same_parity));
+        assert!(!list
+            .as_ref()
+            .eq_by(other.as_ref(), |a, b| a.value == b.value));
+
+        other.as_mut().push_back(MyElement::new(7));
+        assert!(!list.as_ref().eq_by(other.as_ref(), same_parity));
+        assert!(!other.as_ref().eq_by(list.as_ref(), same_parity));
+    }
+
+    #[test]
+    fn test_extend() {
+        let integers = [0, 1, 2, 3, 4, 5];
+
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        list.as_mut()
+            .extend(integers.into_iter().map(MyElement::new));
+
+        for (i, element) in integers.into_iter().zip(list.as_ref().iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        verify_all_links(list.as_ref().inner());
+    }
+
+    #[test]
+    fn test_extend_count() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        list.as_mut().push_back(MyElement::new(0));
+
+        let count = list.as_mut().extend_count(
+            (1..)
+                .map(MyElement::new)
+                .take_while(|element| element.value < 5),
+        );
+        assert_eq!(count, 4);
+        assert_eq!(list.as_mut().extend_count(core::iter::empty()), 0);
+
+        for (i, element) in (0..5).zip(list.as_ref().iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        assert_eq!(list.as_ref().len(), 5);
+        verify_all_links(list.as_ref().inner());
+    }
+
+    #[test]
+    fn test_extract_if_counted() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..7 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        let (extracted, kept) = list
+            .as_mut()
+            .extract_if_counted(|element| element.value % 3 == 0);
+        assert!(extracted.iter().map(|element| element.value).eq([0, 3, 6]));
+        assert_eq!(kept, 4);
+        assert_eq!(list.as_ref().len(), kept);
+        verify_all_links(list.as_ref().inner());
+
+        let (extracted, kept) = list.as_mut().extract_if_counted(|_| false);
+        assert!(extracted.is_empty());
+        assert_eq!(kept, 4);
+    }
+
+    #[test]
+    fn test_find() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..5 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        assert!(list.as_ref().find(|element| element.value == 5).is_none());
+        assert_eq!(
+            list.as_ref()
+                .find(|element| element.value > 2)
+                .unwrap()
+                .value,
+            3
+        );
+
+        list.as_mut()
+            .find_mut(|element| element.value == 1)
+            .unwrap()
+            .value = 10;
+        assert_eq!(list.as_ref().iter().nth(1).unwrap().value, 10);
+    }
+
+    #[test]
+    fn test_first_duplicate() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        assert!(!list.as_ref().has_duplicates());
+
+        for i in [0, 1, 2, 3] {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        assert!(list.as_ref().first_duplicate().is_none());
+        assert!(!list.as_ref().has_duplicates());
+
+        for i in [2, 1] {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        // The second 2 is the first element that repeats an earlier one.
+        let duplicate = list.as_ref().first_duplicate().unwrap();
+        assert_eq!(duplicate.value, 2);
+        assert!(ptr::eq(duplicate, list.as_ref().iter().nth(4).unwrap()));
+        assert!(list.as_ref().has_duplicates());
+    }
+
+    #[test]
+    fn test_fold() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        assert_eq!(list.as_ref().fold(0, |sum, element| sum + element.value), 0);
+
+        for i in 1..5 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        assert_eq!(
+            list.as_ref().fold(0, |sum, element| sum + element.value),
+            10
+        );
+        assert_eq!(
+            list.as_ref()
+                .fold(0, |number, element| number * 10 + element.value),
+            1234
+        );
+    }
+
+    #[test]
+    fn test_for_each_with_cursor() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..6 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        // Inserted elements must not be visited.
+        let mut sum = 0;
+        list.as_mut().for_each_with_cursor(|mut cursor| {
+            let value = cursor.element().value;
+            sum += value;
+
+            match value {
+                1 => {
+                    cursor.insert_after(MyElement::new(10));
+                    cursor.insert_after(MyElement::new(11));
+                }
+                4 => cursor.insert_before(MyElement::new(20)),
+                5 => {
+                    cursor.insert_after(MyElement::new(30));
+                    cursor.remove();
+                }
+                _ if value % 3 == 0 => {
+                    cursor.remove();
+                }
+                _ => (),
+            }
+        });
+
+        assert_eq!(sum, 15);
+        assert!(list
+            .as_ref()
+            .iter()
+            .map(|element| element.value)
+            .eq([1, 10, 11, 2, 20, 4, 30]));
+
+        verify_all_links(list.as_ref().inner());
+    }
+
+    #[test]
+    fn test_from_array() {
+        moveit! {
+            let list = NtBoxingListHead::<MyElement, MyList>::from_array(
+                [0, 1, 2, 3].map(MyElement::new),
+            );
+        }
+
+        assert_eq!(list.as_ref().len(), 4);
+
+        for (i, element) in (0..4).zip(list.as_ref().iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        verify_all_links(list.as_ref().inner());
+    }
+
+    #[test]
+    fn test_front_back_mut() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        assert!(matches!(list.as_mut().front_back_mut(), (None, None)));
+
+        list.as_mut().push_back(MyElement::new(0));
+
+        match list.as_mut().front_back_mut() {
+            (Some(front), None) => assert_eq!(front.value, 0),
+            _ => panic!("expected only a front element"),
+        }
+
+        for i in 1..=3 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        let (front, back) = list.as_mut().front_back_mut();
+        let (front, back) = (front.unwrap(), back.unwrap());
+        assert_eq!(front.value, 0);
+        assert_eq!(back.value, 3);
+        core::mem::swap(&mut front.value, &mut back.value);
+
+        assert_eq!(list.as_ref().front().unwrap().value, 3);
+        assert_eq!(list.as_ref().back().unwrap().value, 0);
+        verify_all_links(list.as_ref().inner());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_index_by() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in [10, 21, 30, 41, 50] {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        // The last element with a key wins.
+        let index = list.as_ref().index_by(|element| element.value % 2);
+        assert_eq!(index.len(), 2);
+        assert_eq!(index[&0].value, 50);
+        assert_eq!(index[&1].value, 41);
+
+        let index = list.as_ref().index_by_multi(|element| element.value % 2);
+        assert_eq!(index.len(), 2);
+        assert!(index[&0]
+            .iter()
+            .map(|element| element.value)
+            .eq([10, 30, 50]));
+        assert!(index[&1].iter().map(|element| element.value).eq([21, 41]));
+    }
+
+    #[test]
+    fn test_init_at() {
+        #[repr(C)]
+        struct ControlBlock {
+            id: u32,
+            list: NtBoxingListHead<MyElement, MyList>,
+        }
+
+        // Initialize the header in place inside a heap-allocated structure.
+        let block = Box::into_raw(Box::new(MaybeUninit::<ControlBlock>::uninit()));
+        let mut block = unsafe {
+            let block = block.cast::<ControlBlock>();
+            ptr::addr_of_mut!((*block).id).write(1);
+            NtBoxingListHead::init_at(ptr::addr_of_mut!((*block).list));
+            Box::from_raw(block)
+        };
+
+        assert_eq!(block.id, 1);
+
+        let mut list = unsafe { Pin::new_unchecked(&mut block.list) };
+        assert!(list.as_ref().is_empty());
+        verify_all_links(list.as_ref().inner());
+
+        for i in 0..5 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        for (i, element) in (0..5).zip(list.as_ref().iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        verify_all_links(list.as_ref().inner());
+    }
+
+    #[test]
+    fn test_iter_back() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..5 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        for element in list.as_mut().iter_back_mut().take(2) {
+            element.value += 10;
+        }
+
+        let values = list
+            .as_ref()
+            .iter_back()
+            .map(|element| element.value)
+            .collect::<Vec<_>>();
+        assert_eq!(values, [14, 13, 2, 1, 0]);
+    }
+
+    #[test]
+    fn test_iter_circular() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        assert!(list.as_ref().iter_circular().next().is_none());
+
+        for i in 0..3 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        assert!(list
+            .as_ref()
+            .iter_circular()
+            .take(8)
+            .map(|element| element.value)
+            .eq([0, 1, 2, 0, 1, 2, 0, 1]));
+    }
+
+    #[test]
+    fn test_iter_enumerated() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for _ in 0..5 {
+            list.as_mut().push_back(MyElement::new(0));
+        }
+
+        for (i, element) in list.as_mut().iter_enumerated_mut() {
+            element.value = i as i32;
+        }
+
+        for (i, element) in list.as_ref().iter_enumerated() {
+            assert_eq!(i as i32, element.value);
+        }
+    }
+
+    #[test]
+    fn test_iter_step_by() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..10 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        let values = list
+            .as_ref()
+            .iter_step_by(3)
+            .map(|element| element.value)
+            .collect::<Vec<_>>();
+        assert_eq!(values, [0, 3, 6, 9]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_iter_step_by_zero() {
+        moveit! {
+            let list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        let _ = list.as_ref().iter_step_by(0);
+    }
+
+    #[test]
+    fn test_iter_with_remover() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..10 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        // Remove every element that would make the sum of the kept elements exceed 10.
+        let mut sum = 0;
+        let mut removed = Vec::new();
+
+        for mut item in list.as_mut().iter_with_remover() {
+            let value = item.element().value;
+
+            if sum + value > 10 {
+                removed.push(item.remove().value);
+            } else {
+                sum += value;
+                item.element().value *= 10;
+            }
+        }
+
+        assert_eq!(removed, [5, 6, 7, 8, 9]);
+
+        for (i, element) in (0..5).zip(list.as_ref().iter()) {
+            assert_eq!(i * 10, element.value);
+        }
+
+        verify_all_links(list.as_ref().inner());
+
+        // Collect all items first and remove adjacent ones out of order afterwards.
+        let mut items = list.as_mut().iter_with_remover().collect::<Vec<_>>();
+        assert_eq!(items.pop().unwrap().remove().value, 40);
+        assert_eq!(items.remove(1).remove().value, 10);
+        assert_eq!(items.remove(0).remove().value, 0);
+        drop(items);
+
+        for (i, element) in [20, 30].into_iter().zip(list.as_ref().iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        assert_eq!(list.as_ref().len(), 2);
+        verify_all_links(list.as_ref().inner());
+    }
+
+    #[test]
+    fn test_leak_all() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        assert!(list.as_mut().leak_all().is_null());
+
+        for i in 0..5 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        let mut current = list.as_mut().leak_all();
+        assert!(list.as_ref().is_empty());
+        verify_all_links(list.as_ref().inner());
+
+        // Take back ownership of all elements, as foreign code would have to.
+        unsafe {
+            assert!((*current).blink.is_null());
+
+            for i in 0..5 {
+                let next = (*current).flink;
+                let element = Box::from_raw(NtListEntry::containing_record_mut(current));
+                assert_eq!(i, element.value);
+                current = next;
+            }
+        }
+
+        assert!(current.is_null());
+
+        // The list can still be used.
+        list.as_mut().push_back(MyElement::new(5));
+        assert_eq!(list.as_ref().len(), 1);
+    }
+
+    #[test]
+    fn test_new_boxed() {
+        extern crate std;
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+        #[derive(NtListElement)]
+        #[repr(C)]
+        struct CountingElement {
+            value: i32,
+            #[boxed]
+            entry: NtListEntry<Self, MyList>,
+        }
+
+        impl Drop for CountingElement {
+            fn drop(&mut self) {
+                DROPPED.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        struct Owner {
+            list: Pin<Box<NtBoxingListHead<CountingElement, MyList>>>,
+        }
+
+        let mut owner = Owner {
+            list: NtBoxingListHead::new_boxed(),
+        };
+        assert!(owner.list.as_ref().is_empty());
+        verify_all_links(owner.list.as_ref().inner());
+
+        for value in 0..5 {
+            owner.list.as_mut().push_back(CountingElement {
+                value,
+                entry: NtListEntry::new(),
+            });
+        }
+
+        // Moving the owner must not affect the heap-allocated header.
+        let owner = std::vec![owner].pop().unwrap();
+
+        assert_eq!(owner.list.as_ref().len(), 5);
+        for (i, element) in (0..5).zip(owner.list.as_ref().iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        verify_all_links(owner.list.as_ref().inner());
+
+        drop(owner);
+        assert_eq!(DROPPED.load(Ordering::SeqCst), 5);
+    }
+
+    #[test]
+    fn test_map_to_vec() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..4 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        let doubled = list.as_ref().map_to_vec(|element| element.value * 2);
+        assert_eq!(doubled, [0, 2, 4, 6]);
+
+        let taken = list
+            .as_mut()
+            .map_to_vec_mut(|element| core::mem::replace(&mut element.value, -1));
+        assert_eq!(taken, [0, 1, 2, 3]);
+        assert!(list.as_ref().iter().all(|element| element.value == -1));
+    }
+
+    #[test]
+    fn test_partition() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+            let mut even = NtBoxingListHead::<MyElement, MyList>::new();
+            let mut odd = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..7 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        list.as_mut().partition(
+            |element| element.value % 2 == 0,
+            even.as_mut(),
+            odd.as_mut(),
+        );
+
+        assert!(list.as_ref().is_empty());
+        assert!(even
+            .as_ref()
+            .iter()
+            .map(|element| element.value)
+            .eq([0, 2, 4, 6]));
+        assert!(odd
+            .as_ref()
+            .iter()
+            .map(|element| element.value)
+            .eq([1, 3, 5]));
+
+        verify_all_links(list.as_ref().inner());
+        verify_all_links(even.as_ref().inner());
+        verify_all_links(odd.as_ref().inner());
+    }
+
+    #[test]
+    #[should_panic(expected = "destination lists must be empty")]
+    fn test_partition_nonempty_destination() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+            let mut matching = NtBoxingListHead::<MyElement, MyList>::new();
+            let mut rest = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        rest.as_mut().push_back(MyElement::new(0));
+        list.as_mut()
+            .partition(|_| true, matching.as_mut(), rest.as_mut());
+    }
+
+    #[test]
+    fn test_pop_back() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..10 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        for i in (0..10).rev() {
+            let element = list.as_mut().pop_back().unwrap();
+            assert_eq!(i, element.value);
+            verify_all_links(list.as_ref().inner());
+        }
+
+        assert!(list.as_ref().is_empty());
+    }
+
+    #[test]
+    fn test_pop_front() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..10 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        for i in 0..10 {
+            let element = list.as_mut().pop_front().unwrap();
+            assert_eq!(i, element.value);
+            verify_all_links(list.as_ref().inner());
+        }
+
+        assert!(list.as_ref().is_empty());
+    }
+
+    #[test]
+    fn test_pop_if() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        assert!(list.as_mut().pop_front_if(|_| true).is_none());
+        assert!(list.as_mut().pop_back_if(|_| true).is_none());
+
+        for i in 0..10 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        // Consume the front while the elements are less than 3.
+        let mut popped = 0;
+        while let Some(element) = list.as_mut().pop_front_if(|element| element.value < 3) {
+            assert_eq!(popped, element.value);
+            popped += 1;
+        }
+        assert_eq!(popped, 3);
+
+        assert!(list
+            .as_mut()
+            .pop_back_if(|element| element.value == 8)
+            .is_none());
+        assert_eq!(
+            list.as_mut()
+                .pop_back_if(|element| element.value == 9)
+                .unwrap()
+                .value,
+            9
+        );
+
+        assert_eq!(list.as_ref().len(), 6);
+        assert_eq!(list.as_ref().front().unwrap().value, 3);
+        assert_eq!(list.as_ref().back().unwrap().value, 8);
+        verify_all_links(list.as_ref().inner());
+    }
+
+    #[test]
+    fn test_pop_n_front() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+            let mut dest = NtBoxingListHead::<MyElement, MyList>::new();
+            let mut dest2 = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..5 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        list.as_mut().pop_n_front(0, dest.as_mut());
+        assert!(dest.as_ref().is_empty());
+
+        list.as_mut().pop_n_front(2, dest.as_mut());
+        assert_eq!(dest.as_ref().len(), 2);
+        assert_eq!(list.as_ref().len(), 3);
+
+        for (i, element) in (0..2).zip(dest.as_ref().iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        for (i, element) in (2..5).zip(list.as_ref().iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        verify_all_links(list.as_ref().inner());
+        verify_all_links(dest.as_ref().inner());
+
+        // Pop more elements than there are.
+        list.as_mut().pop_n_front(10, dest2.as_mut());
+        assert!(list.as_ref().is_empty());
+        assert_eq!(dest2.as_ref().len(), 3);
+        verify_all_links(list.as_ref().inner());
+        verify_all_links(dest2.as_ref().inner());
+    }
+
+    #[test]
+    fn test_push_back() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..10 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        assert_eq!(list.as_ref().len(), 10);
+
+        for (i, element) in (0..10).zip(list.as_ref().iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        verify_all_links(list.as_ref().inner());
+    }
+
+    #[test]
+    fn test_push_front() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..10 {
+            list.as_mut().push_front(MyElement::new(i));
+        }
+
+        assert_eq!(list.as_ref().len(), 10);
+
+        for (i, element) in (0..10).rev().zip(list.as_ref().iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        verify_all_links(list.as_ref().inner());
+    }
+
+    #[test]
+    fn test_repair() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..5 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        // Destroy all backward links, leaving only the forward chain intact.
+        for element in list.as_mut().iter_mut() {
+            element.entry.blink = ptr::null_mut();
+        }
+        unsafe { list.as_mut().get_unchecked_mut().0.blink = ptr::null_mut() };
+
+        unsafe { list.as_mut().inner_mut().repair() };
+        verify_all_links(list.as_ref().inner());
+
+        for (i, element) in (0..5).rev().zip(list.as_ref().iter().rev()) {
+            assert_eq!(i, element.value);
+        }
+
+        // Repairing an empty list must point the header back to itself.
+        list.as_mut().clear();
+        unsafe { list.as_mut().get_unchecked_mut().0.blink = ptr::null_mut() };
+        unsafe { list.as_mut().inner_mut().repair() };
+        assert!(list.as_ref().is_empty());
+        verify_all_links(list.as_ref().inner());
+    }
+
+    #[test]
+    fn test_retain() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..10 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        // Keep only the even elements.
+        list.as_mut().retain(|element| element.value % 2 == 0);
+
+        assert_eq!(list.as_ref().len(), 5);
+
+        for (i, element) in (0..10).step_by(2).zip(list.as_ref().iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        verify_all_links(list.as_ref().inner());
+
+        // Keep only the first and last of the remaining elements.
+        list.as_mut()
+            .retain(|element| element.value == 0 || element.value == 8);
+
+        let mut iter = list.as_ref().iter();
+        assert_eq!(iter.next().unwrap().value, 0);
+        assert_eq!(iter.next().unwrap().value, 8);
+        assert!(matches!(iter.next(), None));
+    }
+
+    #[test]
+    fn test_retain_budgeted() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..10 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        // Remove at most 2 odd elements per call and stop visiting elements afterwards.
+        let mut visited = 0;
+        let removed = list.as_mut().retain_budgeted(2, |element| {
+            visited += 1;
+            element.value % 2 == 0
+        });
+        assert_eq!(removed, 2);
+        assert_eq!(visited, 4);
+        assert!(list
+            .as_ref()
+            .iter()
+            .map(|element| element.value)
+            .eq([0, 2, 4, 5, 6, 7, 8, 9]));
+        verify_all_links(list.as_ref().inner());
+
+        // A budget larger than necessary behaves like `retain`.
+        let removed = list
+            .as_mut()
+            .retain_budgeted(10, |element| element.value % 2 == 0);
+        assert_eq!(removed, 3);
+        assert!(list
+            .as_ref()
+            .iter()
+            .map(|element| element.value)
+            .eq([0, 2, 4, 6, 8]));
+        verify_all_links(list.as_ref().inner());
+
+        // A budget of 0 doesn't visit any element.
+        let removed = list.as_mut().retain_budgeted(0, |_| unreachable!());
+        assert_eq!(removed, 0);
+        assert_eq!(list.as_ref().len(), 5);
+    }
+
+    #[test]
+    fn test_retain_panic() {
+        extern crate std;
+        use core::sync::atomic::{AtomicUsize, Ordering};
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+        #[derive(NtListElement)]
+        #[repr(C)]
+        struct PanickingElement {
+            value: i32,
+            #[boxed]
+            entry: NtListEntry<Self, MyList>,
+        }
+
+        impl Drop for PanickingElement {
+            fn drop(&mut self) {
+                DROPPED.fetch_add(1, Ordering::SeqCst);
+
+                if self.value == 2 {
+                    panic!("dropping element 2");
+                }
+            }
+        }
+
+        {
+            moveit! {
+                let mut list = NtBoxingListHead::<PanickingElement, MyList>::new();
+            }
+
+            for value in 0..6 {
+                list.as_mut().push_back(PanickingElement {
+                    value,
+                    entry: NtListEntry::new(),
+                });
+            }
+
+            // Remove the even elements. Dropping element 2 panics.
+            let result = catch_unwind(AssertUnwindSafe(|| {
+                list.as_mut().retain(|element| element.value % 2 == 1)
+            }));
+            assert!(result.is_err());
+            assert_eq!(DROPPED.load(Ordering::SeqCst), 2);
+
+            // Element 2 must no longer be part of the list, all others after it must be.
+            assert_eq!(list.as_ref().len(), 4);
+            for (value, element) in [1, 3, 4, 5].iter().zip(list.as_ref().iter()) {
+                assert_eq!(*value, element.value);
+            }
+
+            verify_all_links(list.as_ref().inner());
+        }
+
+        // Dropping the list must only drop the remaining elements, not element 2 a second time.
+        assert_eq!(DROPPED.load(Ordering::SeqCst), 6);
+    }
+
+    #[test]
+    fn test_retain_until() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..7 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        let mut visited = 0;
+
+        // Remove the odd elements below 4 and keep everything from 4 onwards.
+        list.as_mut().retain_until(|element| {
+            visited += 1;
+
+            if element.value >= 4 {
+                Decision::StopKeepingRest
+            } else if element.value % 2 == 1 {
+                Decision::Remove
+            } else {
+                Decision::Keep
+            }
+        });
+
+        assert_eq!(visited, 5);
+        assert!(list
+            .as_ref()
+            .iter()
+            .map(|element| element.value)
+            .eq([0, 2, 4, 5, 6]));
+        verify_all_links(list.as_ref().inner());
+
+        list.as_mut().retain_until(|_| Decision::Remove);
+        assert!(list.as_ref().is_empty());
+    }
+
+    #[test]
+    fn test_retain_until_side_effects() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..10 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        // Sum up all elements and remove adjacent ones, including the first and last one.
+        let mut sum = 0;
+        list.as_mut().retain_until(|element| {
+            sum += element.value;
+
+            if matches!(element.value, 0 | 4 | 5 | 9) {
+                Decision::Remove
+            } else {
+                Decision::Keep
+            }
+        });
+
+        assert_eq!(sum, 45);
+        assert_eq!(list.as_ref().len(), 6);
+
+        for (i, element) in [1, 2, 3, 6, 7, 8].into_iter().zip(list.as_ref().iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        verify_all_links(list.as_ref().inner());
+    }
+
+    #[test]
+    fn test_reverse_in_groups() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..8 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        // Keep the trailing partial group.
+        list.as_mut().reverse_in_groups(3, false);
+        assert!(list
+            .as_ref()
+            .iter()
+            .map(|element| element.value)
+            .eq([2, 1, 0, 5, 4, 3, 6, 7]));
+        verify_all_links(list.as_ref().inner());
+
+        // Reverse the trailing partial group, undoing the previous call for all full groups.
+        list.as_mut().reverse_in_groups(3, true);
+        assert!(list
+            .as_ref()
+            .iter()
+            .map(|element| element.value)
+            .eq([0, 1, 2, 3, 4, 5, 7, 6]));
+        verify_all_links(list.as_ref().inner());
+
+        // A group size of 1 and a single group spanning the entire list.
+        list.as_mut().reverse_in_groups(1, true);
+        list.as_mut().reverse_in_groups(8, false);
+        assert!(list
+            .as_ref()
+            .iter()
+            .map(|element| element.value)
+            .eq([6, 7, 5, 4, 3, 2, 1, 0]));
+        verify_all_links(list.as_ref().inner());
+
+        // A group larger than the list is a trailing partial group.
+        list.as_mut().reverse_in_groups(10, false);
+        assert_eq!(list.as_ref().front().unwrap().value, 6);
+        list.as_mut().reverse_in_groups(10, true);
+        assert_eq!(list.as_ref().front().unwrap().value, 0);
+        verify_all_links(list.as_ref().inner());
+    }
+
+    #[test]
+    #[should_panic(expected = "group size must not be 0")]
+    fn test_reverse_in_groups_zero() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        list.as_mut().reverse_in_groups(0, true);
+    }
+
+    #[test]
+    fn test_reverse_range() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..6 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        // Empty and single-element ranges leave the list untouched.
+        list.as_mut().reverse_range(2..2);
+        list.as_mut().reverse_range(2..3);
+
+        // Reverse a range in the middle.
+        list.as_mut().reverse_range(1..4);
+
+        for (i, element) in [0, 3, 2, 1, 4, 5].into_iter().zip(list.as_ref().iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        verify_all_links(list.as_ref().inner());
 
-        unsafe {
-            self.as_mut().get_unchecked_mut().0.blink = previous;
+        // Reverse the entire list, touching the header on both sides.
+        list.as_mut().reverse_range(0..6);
+
+        for (i, element) in [5, 4, 1, 2, 3, 0].into_iter().zip(list.as_ref().iter()) {
+            assert_eq!(i, element.value);
         }
-    }
-}
 
-impl<E, L> Extend<E> for Pin<&mut NtBoxingListHead<E, L>>
-where
-    E: NtBoxedListElement<L = L> + NtListElement<L>,
-    L: NtTypedList<T = NtList>,
-{
-    fn extend<T>(&mut self, iter: T)
-    where
-        T: IntoIterator<Item = E>,
-    {
-        self.extend(iter.into_iter().map(Box::new))
+        verify_all_links(list.as_ref().inner());
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::list::NtListEntry;
-    use alloc::vec::Vec;
-    use moveit::moveit;
+    #[test]
+    #[should_panic(expected = "range end index 7 out of range for list of length 6")]
+    fn test_reverse_range_out_of_bounds() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
 
-    #[derive(NtList)]
-    enum MyList {}
+        for i in 0..6 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
 
-    #[derive(Default, NtListElement)]
-    #[repr(C)]
-    struct MyElement {
-        value: i32,
-        #[boxed]
-        entry: NtListEntry<Self, MyList>,
+        list.as_mut().reverse_range(4..7);
     }
 
-    impl MyElement {
-        fn new(value: i32) -> Self {
-            Self {
-                value,
-                ..Default::default()
-            }
+    #[test]
+    #[should_panic(expected = "range start index 7 out of range for list of length 6")]
+    fn test_reverse_range_start_out_of_bounds() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..6 {
+            list.as_mut().push_back(MyElement::new(i));
         }
+
+        list.as_mut().reverse_range(7..8);
     }
 
     #[test]
-    fn test_append() {
-        // Append two lists of equal size.
+    fn test_rotate_to() {
         moveit! {
-            let mut list1 = NtBoxingListHead::<MyElement, MyList>::new();
-            let mut list2 = NtBoxingListHead::<MyElement, MyList>::new();
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
         }
 
-        for i in 0..10 {
-            list1.as_mut().push_back(MyElement::new(i));
-            list2.as_mut().push_back(MyElement::new(i));
+        for i in 0..5 {
+            list.as_mut().push_back(MyElement::new(i));
         }
 
-        list1.as_mut().append(list2.as_mut());
-
-        assert_eq!(list1.as_ref().len(), 20);
-        assert_eq!(list2.as_ref().len(), 0);
+        // Rotating to the first element is a no-op.
+        let first = list.as_mut().front_mut().unwrap() as *mut MyElement;
+        unsafe { list.as_mut().rotate_to(&mut *first) };
 
-        for (i, element) in (0..10).chain(0..10).zip(list1.as_ref().iter()) {
+        for (i, element) in (0..5).zip(list.as_ref().iter()) {
             assert_eq!(i, element.value);
         }
 
-        verify_all_links(list1.as_ref().inner());
+        // Rotate to an element in the middle.
+        let middle = list.as_mut().iter_mut().nth(2).unwrap() as *mut MyElement;
+        unsafe { list.as_mut().rotate_to(&mut *middle) };
 
-        // Append the final list to an empty list.
-        moveit! {
-            let mut list3 = NtBoxingListHead::<MyElement, MyList>::new();
+        for (i, element) in [2, 3, 4, 0, 1].into_iter().zip(list.as_ref().iter()) {
+            assert_eq!(i, element.value);
         }
 
-        list3.as_mut().append(list1.as_mut());
+        verify_all_links(list.as_ref().inner());
 
-        assert_eq!(list3.as_ref().len(), 20);
-        assert_eq!(list1.as_ref().len(), 0);
+        // Rotate to the last element.
+        let last = list.as_mut().back_mut().unwrap() as *mut MyElement;
+        unsafe { list.as_mut().rotate_to(&mut *last) };
 
-        verify_all_links(list3.as_ref().inner());
+        for (i, element) in [1, 2, 3, 4, 0].into_iter().zip(list.as_ref().iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        assert_eq!(list.as_ref().len(), 5);
+        verify_all_links(list.as_ref().inner());
     }
 
     #[test]
-    fn test_clear_and_append() {
-        // Append two lists of equal size.
+    fn test_rotate_to_value() {
         moveit! {
-            let mut list1 = NtBoxingListHead::<MyElement, MyList>::new();
-            let mut list2 = NtBoxingListHead::<MyElement, MyList>::new();
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
         }
 
-        for i in 0..10 {
-            list1.as_mut().push_back(MyElement::new(i));
-            list2.as_mut().push_back(MyElement::new(i));
+        for i in 0..5 {
+            list.as_mut().push_back(MyElement::new(i));
         }
 
-        list1.as_mut().append(list2.as_mut());
-
-        assert_eq!(list1.as_ref().len(), 20);
-        assert_eq!(list2.as_ref().len(), 0);
+        assert!(!list.as_mut().rotate_to_value(&MyElement::new(5)));
 
-        for (i, element) in (0..10).chain(0..10).zip(list1.as_ref().iter()) {
+        for (i, element) in (0..5).zip(list.as_ref().iter()) {
             assert_eq!(i, element.value);
         }
 
-        verify_all_links(list1.as_ref().inner());
+        assert!(list.as_mut().rotate_to_value(&MyElement::new(3)));
 
-        // Add more elements to both lists
-        list1.as_mut().push_back(MyElement::new(21));
-        list1.as_mut().push_front(MyElement::new(22));
+        for (i, element) in [3, 4, 0, 1, 2].into_iter().zip(list.as_ref().iter()) {
+            assert_eq!(i, element.value);
+        }
 
-        list2.as_mut().push_back(MyElement::new(21));
-        list2.as_mut().push_front(MyElement::new(22));
+        verify_all_links(list.as_ref().inner());
+    }
 
-        // Append the final list to a cleared list.
+    #[test]
+    fn test_rposition() {
         moveit! {
-            let mut list3 = NtBoxingListHead::<MyElement, MyList>::new();
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
         }
 
-        list3.as_mut().clear();
-        list3.as_mut().append(list1.as_mut());
+        assert_eq!(list.as_ref().rposition(|_| true), None);
 
-        assert_eq!(list3.as_ref().len(), 22);
-        assert_eq!(list1.as_ref().len(), 0);
+        for i in 0..10 {
+            list.as_mut().push_back(MyElement::new(i % 5));
+        }
 
-        verify_all_links(list3.as_ref().inner());
+        assert_eq!(
+            list.as_ref().rposition(|element| element.value == 0),
+            Some(5)
+        );
+        assert_eq!(
+            list.as_ref().rposition(|element| element.value == 4),
+            Some(9)
+        );
+        assert_eq!(
+            list.as_ref().rposition(|element| element.value < 2),
+            Some(6)
+        );
+        assert_eq!(list.as_ref().rposition(|element| element.value == 5), None);
     }
 
     #[test]
-    fn test_clear_and_push() {
+    fn test_split_at_value() {
         moveit! {
             let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+            let mut dest = NtBoxingListHead::<MyElement, MyList>::new();
         }
 
-        list.as_mut().clear();
-
-        for i in 0..=3 {
+        for i in [1, 3, 3, 5, 7] {
             list.as_mut().push_back(MyElement::new(i));
         }
-        for i in 4..=6 {
-            list.as_mut().push_front(MyElement::new(i));
-        }
 
-        assert_eq!(list.as_ref().back().unwrap().value, 3);
-        assert_eq!(list.as_mut().back_mut().unwrap().value, 3);
-        assert_eq!(list.as_ref().front().unwrap().value, 6);
-        assert_eq!(list.as_mut().front_mut().unwrap().value, 6);
+        // No element is greater than or equal to the key.
+        list.as_mut()
+            .split_at_value(&MyElement::new(8), dest.as_mut());
+        assert_eq!(list.as_ref().len(), 5);
+        assert!(dest.as_ref().is_empty());
+
+        list.as_mut()
+            .split_at_value(&MyElement::new(3), dest.as_mut());
+        assert!(list.as_ref().iter().map(|element| element.value).eq([1]));
+        assert!(dest
+            .as_ref()
+            .iter()
+            .map(|element| element.value)
+            .eq([3, 3, 5, 7]));
+        verify_all_links(list.as_ref().inner());
+        verify_all_links(dest.as_ref().inner());
 
+        // All elements are greater than or equal to the key.
+        dest.as_mut().clear();
+        list.as_mut()
+            .split_at_value(&MyElement::new(0), dest.as_mut());
+        assert!(list.as_ref().is_empty());
+        assert!(dest.as_ref().iter().map(|element| element.value).eq([1]));
         verify_all_links(list.as_ref().inner());
+        verify_all_links(dest.as_ref().inner());
     }
 
     #[test]
-    fn test_back_and_front() {
+    fn test_split_first_and_last() {
         moveit! {
             let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+            let mut rest = NtBoxingListHead::<MyElement, MyList>::new();
+            let mut rest2 = NtBoxingListHead::<MyElement, MyList>::new();
         }
 
-        for i in 0..=3 {
+        assert!(list.as_mut().split_first(rest.as_mut()).is_none());
+        assert!(rest.as_ref().is_empty());
+
+        for i in 0..5 {
             list.as_mut().push_back(MyElement::new(i));
         }
 
-        assert_eq!(list.as_ref().back().unwrap().value, 3);
-        assert_eq!(list.as_mut().back_mut().unwrap().value, 3);
-        assert_eq!(list.as_ref().front().unwrap().value, 0);
-        assert_eq!(list.as_mut().front_mut().unwrap().value, 0);
+        let first = list.as_mut().split_first(rest.as_mut()).unwrap();
+        assert_eq!(first.value, 0);
+        assert!(list.as_ref().is_empty());
+        verify_all_links(list.as_ref().inner());
+
+        let last = rest.as_mut().split_last(rest2.as_mut()).unwrap();
+        assert_eq!(last.value, 4);
+        assert!(rest.as_ref().is_empty());
+        verify_all_links(rest.as_ref().inner());
+
+        for (i, element) in (1..4).zip(rest2.as_ref().iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        assert_eq!(rest2.as_ref().len(), 3);
+        verify_all_links(rest2.as_ref().inner());
     }
 
     #[test]
-    fn test_extend() {
-        let integers = [0, 1, 2, 3, 4, 5];
-
+    fn test_split_off_after() {
         moveit! {
             let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+            let mut dest = NtBoxingListHead::<MyElement, MyList>::new();
         }
 
-        list.as_mut()
-            .extend(integers.into_iter().map(MyElement::new));
+        for i in 0..10 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
 
-        for (i, element) in integers.into_iter().zip(list.as_ref().iter()) {
+        // Splitting after the last element moves nothing.
+        let last = list.as_mut().back_mut().unwrap() as *mut MyElement;
+        unsafe { list.as_mut().split_off_after(&mut *last, dest.as_mut()) };
+
+        assert_eq!(list.as_ref().len(), 10);
+        assert!(dest.as_ref().is_empty());
+        verify_all_links(dest.as_ref().inner());
+
+        // Split after the fourth element.
+        let fourth = list.as_mut().iter_mut().nth(3).unwrap() as *mut MyElement;
+        unsafe { list.as_mut().split_off_after(&mut *fourth, dest.as_mut()) };
+
+        assert_eq!(list.as_ref().len(), 4);
+        assert_eq!(dest.as_ref().len(), 6);
+
+        for (i, element) in (0..4).zip(list.as_ref().iter()) {
+            assert_eq!(i, element.value);
+        }
+        for (i, element) in (4..10).zip(dest.as_ref().iter()) {
             assert_eq!(i, element.value);
         }
 
         verify_all_links(list.as_ref().inner());
+        verify_all_links(dest.as_ref().inner());
     }
 
     #[test]
-    fn test_pop_back() {
+    fn test_swap_ends() {
         moveit! {
             let mut list = NtBoxingListHead::<MyElement, MyList>::new();
         }
 
-        for i in 0..10 {
+        // Swapping the ends of an empty list or a list with a single element is a no-op.
+        list.as_mut().swap_ends();
+        assert!(list.as_ref().is_empty());
+
+        list.as_mut().push_back(MyElement::new(0));
+        list.as_mut().swap_ends();
+        assert_eq!(list.as_ref().front().unwrap().value, 0);
+        verify_all_links(list.as_ref().inner());
+
+        // Swap two adjacent elements.
+        list.as_mut().push_back(MyElement::new(1));
+        list.as_mut().swap_ends();
+
+        for (i, element) in [1, 0].into_iter().zip(list.as_ref().iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        verify_all_links(list.as_ref().inner());
+
+        // Swap the ends of a longer list.
+        for i in 2..5 {
             list.as_mut().push_back(MyElement::new(i));
         }
 
-        for i in (0..10).rev() {
-            let element = list.as_mut().pop_back().unwrap();
+        list.as_mut().swap_ends();
+
+        for (i, element) in [4, 0, 2, 3, 1].into_iter().zip(list.as_ref().iter()) {
             assert_eq!(i, element.value);
-            verify_all_links(list.as_ref().inner());
         }
 
-        assert!(list.as_ref().is_empty());
+        verify_all_links(list.as_ref().inner());
     }
 
     #[test]
-    fn test_pop_front() {
+    fn test_swap_with_next() {
         moveit! {
             let mut list = NtBoxingListHead::<MyElement, MyList>::new();
         }
 
-        for i in 0..10 {
+        for i in 0..4 {
             list.as_mut().push_back(MyElement::new(i));
         }
 
-        for i in 0..10 {
-            let element = list.as_mut().pop_front().unwrap();
+        // Swap the first element with the second one.
+        let first = list.as_mut().front_mut().unwrap() as *mut MyElement;
+        unsafe { list.as_mut().swap_with_next(&mut *first) };
+
+        // Swap the (now) third element with the last one.
+        let third = list.as_mut().iter_mut().nth(2).unwrap() as *mut MyElement;
+        unsafe { list.as_mut().swap_with_next(&mut *third) };
+
+        // Swapping the last element is a no-op.
+        let last = list.as_mut().back_mut().unwrap() as *mut MyElement;
+        unsafe { list.as_mut().swap_with_next(&mut *last) };
+
+        for (i, element) in [1, 0, 3, 2].into_iter().zip(list.as_ref().iter()) {
             assert_eq!(i, element.value);
-            verify_all_links(list.as_ref().inner());
         }
 
-        assert!(list.as_ref().is_empty());
+        verify_all_links(list.as_ref().inner());
     }
 
     #[test]
-    fn test_push_back() {
+    fn test_take_while_count() {
         moveit! {
             let mut list = NtBoxingListHead::<MyElement, MyList>::new();
         }
 
-        for i in 0..10 {
+        assert_eq!(list.as_ref().take_while_count(|_| true), 0);
+
+        for i in [1, 2, 3, 10, 4, 5] {
             list.as_mut().push_back(MyElement::new(i));
         }
 
-        assert_eq!(list.as_ref().len(), 10);
+        assert_eq!(
+            list.as_ref().take_while_count(|element| element.value < 5),
+            3
+        );
+        assert_eq!(
+            list.as_ref().take_while_count(|element| element.value > 5),
+            0
+        );
+        assert_eq!(list.as_ref().take_while_count(|_| true), 6);
 
-        for (i, element) in (0..10).zip(list.as_ref().iter()) {
+        // Consume all elements before the first failing one.
+        let count = list.as_ref().take_while_count(|element| element.value < 5);
+        let consumed = list
+            .as_mut()
+            .drain_range(0..count)
+            .map(|element| element.value);
+        assert!(consumed.eq([1, 2, 3]));
+        assert_eq!(list.as_ref().front().unwrap().value, 10);
+    }
+
+    #[test]
+    fn test_to_set() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in [3, 1, 3, 2, 1] {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        let set = list.as_ref().to_btree_set();
+        assert_eq!(set.len(), 3);
+
+        for (i, element) in (1..4).zip(set) {
             assert_eq!(i, element.value);
         }
 
-        verify_all_links(list.as_ref().inner());
+        #[cfg(feature = "std")]
+        {
+            let set = list.as_ref().to_hash_set();
+            assert_eq!(set.len(), 3);
+            assert!(set.contains(&MyElement::new(2)));
+        }
+
+        // The list itself is unchanged.
+        assert_eq!(list.as_ref().len(), 5);
     }
 
     #[test]
-    fn test_push_front() {
+    fn test_try_push() {
         moveit! {
             let mut list = NtBoxingListHead::<MyElement, MyList>::new();
         }
 
-        for i in 0..10 {
-            list.as_mut().push_front(MyElement::new(i));
+        for i in 0..5 {
+            assert!(list.as_mut().try_push_back(MyElement::new(i)).is_ok());
+        }
+        for i in 5..10 {
+            assert!(list.as_mut().try_push_front(MyElement::new(i)).is_ok());
         }
 
         assert_eq!(list.as_ref().len(), 10);
 
-        for (i, element) in (0..10).rev().zip(list.as_ref().iter()) {
+        for (i, element) in (5..10).rev().chain(0..5).zip(list.as_ref().iter()) {
             assert_eq!(i, element.value);
         }
 
@@ -550,7 +4661,31 @@ mod tests {
     }
 
     #[test]
-    fn test_retain() {
+    fn test_unlink_boxed() {
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..5 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        // Let the first, a middle, and the last element remove themselves.
+        for index in [0, 2, 2] {
+            let element = list.as_mut().iter_mut().nth(index).unwrap() as *mut MyElement;
+            let element = unsafe { unlink_boxed(&mut *element) };
+            assert!(!list.as_ref().contains_ptr(&*element));
+            verify_all_links(list.as_ref().inner());
+        }
+
+        let mut iter = list.as_ref().iter();
+        assert_eq!(iter.next().unwrap().value, 1);
+        assert_eq!(iter.next().unwrap().value, 2);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_update_retain() {
         moveit! {
             let mut list = NtBoxingListHead::<MyElement, MyList>::new();
         }
@@ -559,25 +4694,61 @@ mod tests {
             list.as_mut().push_back(MyElement::new(i));
         }
 
-        // Keep only the even elements.
-        list.as_mut().retain(|element| element.value % 2 == 0);
+        // Drop the odd elements and multiply the even ones by 10.
+        list.as_mut().update_retain(|mut element| {
+            if element.value % 2 == 0 {
+                element.value *= 10;
+                Some(element)
+            } else {
+                None
+            }
+        });
 
         assert_eq!(list.as_ref().len(), 5);
 
-        for (i, element) in (0..10).step_by(2).zip(list.as_ref().iter()) {
+        for (i, element) in (0..100).step_by(20).zip(list.as_ref().iter()) {
             assert_eq!(i, element.value);
         }
 
         verify_all_links(list.as_ref().inner());
+    }
 
-        // Keep only the first and last of the remaining elements.
-        list.as_mut()
-            .retain(|element| element.value == 0 || element.value == 8);
+    #[test]
+    fn test_update_retain_panic() {
+        extern crate std;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
 
-        let mut iter = list.as_ref().iter();
-        assert_eq!(iter.next().unwrap().value, 0);
-        assert_eq!(iter.next().unwrap().value, 8);
-        assert!(matches!(iter.next(), None));
+        moveit! {
+            let mut list = NtBoxingListHead::<MyElement, MyList>::new();
+        }
+
+        for i in 0..10 {
+            list.as_mut().push_back(MyElement::new(i));
+        }
+
+        // Update elements like in `test_update_retain`, but panic on element 5.
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            list.as_mut().update_retain(|mut element| {
+                assert_ne!(element.value, 5, "updating element 5");
+
+                if element.value % 2 == 0 {
+                    element.value *= 10;
+                    Some(element)
+                } else {
+                    None
+                }
+            })
+        }));
+        assert!(result.is_err());
+
+        // Element 5 is gone, and all other elements keep their order.
+        assert!(list
+            .as_ref()
+            .iter()
+            .map(|element| element.value)
+            .eq([0, 20, 40, 6, 7, 8, 9]));
+
+        verify_all_links(list.as_ref().inner());
     }
 
     fn verify_all_links<E, L>(head: Pin<&NtListHead<E, L>>)
@@ -1,8 +1,11 @@
 // Copyright 2022 Colin Finck <colin@reactos.org>
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
+use core::alloc::Layout;
+
+use super::base::NtListEntry;
 use crate::private::Sealed;
-use crate::traits::NtListType;
+use crate::traits::{NtListElement, NtListType, NtTypedList};
 
 /// Designates a list as an NT doubly linked list (`LIST_ENTRY` structure of the Windows NT API).
 ///
@@ -13,7 +16,15 @@ pub enum NtList {}
 
 /// Doubly linked list type (`LIST_ENTRY` structure of the Windows NT API)
 impl NtListType for NtList {}
-impl Sealed for NtList {}
+impl Sealed for NtList {
+    fn entry_layout<E, L>() -> Layout
+    where
+        E: NtListElement<L>,
+        L: NtTypedList<T = Self>,
+    {
+        Layout::new::<NtListEntry<E, L>>()
+    }
+}
 
 /// Designates an empty enum as a doubly linked list.
 ///
@@ -1,5 +1,15 @@
 // Copyright 2022 Colin Finck <colin@reactos.org>
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
+use core::alloc::Layout;
+
+use crate::traits::{NtListElement, NtTypedList};
+
 // https://rust-lang.github.io/api-guidelines/future-proofing.html
-pub trait Sealed {}
+pub trait Sealed {
+    /// Returns the memory layout of the entry field that an element needs for a list of this type.
+    fn entry_layout<E, L>() -> Layout
+    where
+        E: NtListElement<L>,
+        L: NtTypedList<T = Self>;
+}
@@ -0,0 +1,209 @@
+// Copyright 2026 Colin Finck <colin@reactos.org>
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use bumpalo::Bump;
+
+use super::base::{Iter, IterMut, NtSingleListHead};
+use super::traits::NtSingleList;
+use crate::traits::{NtBoxedListElement, NtListElement, NtTypedList};
+
+/// A variant of [`NtSingleListHead`] that allocates every element in a [`Bump`] arena on insertion.
+///
+/// Like [`NtBoxingSingleListHead`], this list owns its elements and all functions can be used
+/// without resorting to `unsafe`.
+/// However, elements are not allocated individually on the heap, but in the arena passed to
+/// [`new`](Self::new).
+/// This makes building large transient lists considerably faster.
+///
+/// Elements are never deallocated individually.
+/// Their memory is only reclaimed when the arena is reset or dropped.
+/// Just like for any other [`Bump`] allocation, the `Drop` handlers of the elements are not called.
+/// Consequently, [`pop_front`](Self::pop_front) returns a mutable reference into the arena
+/// instead of an owned element.
+///
+/// You need to implement the [`NtBoxedListElement`] trait to designate a single list as the owning one.
+///
+/// See the [module-level documentation](crate::single_list) for more details.
+///
+/// [`NtBoxingSingleListHead`]: crate::single_list::NtBoxingSingleListHead
+#[cfg_attr(docsrs, doc(cfg(feature = "bumpalo")))]
+pub struct ArenaNtBoxingSingleListHead<
+    'a,
+    E: NtBoxedListElement<L = L> + NtListElement<L>,
+    L: NtTypedList<T = NtSingleList>,
+> {
+    head: NtSingleListHead<E, L>,
+    arena: &'a Bump,
+}
+
+impl<'a, E, L> ArenaNtBoxingSingleListHead<'a, E, L>
+where
+    E: NtBoxedListElement<L = L> + NtListElement<L>,
+    L: NtTypedList<T = NtSingleList>,
+{
+    /// Creates a new singly linked list that allocates all elements in `arena`.
+    pub fn new(arena: &'a Bump) -> Self {
+        Self {
+            head: NtSingleListHead::new(),
+            arena,
+        }
+    }
+
+    /// Returns the arena that all elements of this list are allocated in.
+    pub fn arena(&self) -> &'a Bump {
+        self.arena
+    }
+
+    /// Removes all elements from the list.
+    ///
+    /// Unlike [`NtBoxingSingleListHead::clear`], this operation computes in *O*(*1*) time,
+    /// because the memory of the elements is only reclaimed together with the arena.
+    ///
+    /// [`NtBoxingSingleListHead::clear`]: crate::single_list::NtBoxingSingleListHead::clear
+    pub fn clear(&mut self) {
+        self.head.clear();
+    }
+
+    /// Provides a reference to the first element, or `None` if the list is empty.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn front(&self) -> Option<&E> {
+        unsafe { self.head.front() }
+    }
+
+    /// Provides a mutable reference to the first element, or `None` if the list is empty.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn front_mut(&mut self) -> Option<&mut E> {
+        unsafe { self.head.front_mut() }
+    }
+
+    /// Returns `true` if the list is empty.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn is_empty(&self) -> bool {
+        self.head.is_empty()
+    }
+
+    /// Returns an iterator yielding references to each element of the list.
+    pub fn iter(&self) -> Iter<'_, E, L> {
+        unsafe { self.head.iter() }
+    }
+
+    /// Returns an iterator yielding mutable references to each element of the list.
+    pub fn iter_mut(&mut self) -> IterMut<'_, E, L> {
+        unsafe { self.head.iter_mut() }
+    }
+
+    /// Counts all elements and returns the length of the list.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn len(&self) -> usize {
+        unsafe { self.head.len() }
+    }
+
+    /// Removes the first element from the list and returns a reference to it, or `None` if the
+    /// list is empty.
+    ///
+    /// The element stays allocated in the arena for its entire lifetime.
+    ///
+    /// This function substitutes [`PopEntryList`] of the Windows NT API.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    ///
+    /// [`PopEntryList`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-popentrylist
+    pub fn pop_front(&mut self) -> Option<&'a mut E> {
+        // The element has been unlinked and is only referenced by the arena from now on.
+        unsafe {
+            self.head
+                .pop_front()
+                .map(|element| &mut *(element as *mut E))
+        }
+    }
+
+    /// Allocates an element in the arena and appends it to the front of the list.
+    ///
+    /// This function substitutes [`PushEntryList`] of the Windows NT API.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    ///
+    /// [`PushEntryList`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-pushentrylist
+    pub fn push_front(&mut self, element: E) {
+        let element = self.arena.alloc(element);
+        unsafe { self.head.push_front(element) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::single_list::NtSingleListEntry;
+
+    #[derive(NtSingleList)]
+    enum MyList {}
+
+    #[derive(Default, NtListElement)]
+    #[repr(C)]
+    struct MyElement {
+        value: i32,
+        #[boxed]
+        entry: NtSingleListEntry<Self, MyList>,
+    }
+
+    impl MyElement {
+        fn new(value: i32) -> Self {
+            Self {
+                value,
+                ..Default::default()
+            }
+        }
+    }
+
+    #[test]
+    fn test_pop_front() {
+        let arena = Bump::new();
+        let mut list = ArenaNtBoxingSingleListHead::<MyElement, MyList>::new(&arena);
+
+        for i in 0..10 {
+            list.push_front(MyElement::new(i));
+        }
+
+        let popped = [0, 1, 2].map(|_| list.pop_front().unwrap());
+        popped[0].value = 42;
+
+        assert_eq!(popped[0].value, 42);
+        assert_eq!(popped[1].value, 8);
+        assert_eq!(popped[2].value, 7);
+        assert_eq!(list.len(), 7);
+
+        for (i, element) in (0..7).rev().zip(list.iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        list.clear();
+        assert!(list.is_empty());
+        assert!(list.pop_front().is_none());
+    }
+
+    #[test]
+    fn test_push_front() {
+        let arena = Bump::new();
+        let mut list = ArenaNtBoxingSingleListHead::<MyElement, MyList>::new(&arena);
+
+        for i in 0..10 {
+            list.push_front(MyElement::new(i));
+        }
+
+        assert_eq!(list.len(), 10);
+        assert_eq!(list.front().unwrap().value, 9);
+        assert!(arena.allocated_bytes() > 0);
+
+        for element in list.iter_mut() {
+            element.value *= 2;
+        }
+
+        for (i, element) in (0..10).rev().zip(list.iter()) {
+            assert_eq!(i * 2, element.value);
+        }
+    }
+}
@@ -1,11 +1,15 @@
 // Copyright 2022 Colin Finck <colin@reactos.org>
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
+use core::any;
+use core::fmt;
 use core::iter::FusedIterator;
 use core::marker::PhantomData;
+use core::mem;
 use core::ptr;
 
 use super::traits::NtSingleList;
+use crate::list::{NtList, NtListEntry, NtListHead};
 use crate::traits::{NtListElement, NtTypedList};
 
 /// A singly linked list header compatible to [`SINGLE_LIST_ENTRY`] of the Windows NT API.
@@ -46,8 +50,38 @@ where
         self.next = ptr::null_mut();
     }
 
+    /// Returns `true` if `entry` is part of this list.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    #[cfg(feature = "alloc")]
+    pub(crate) unsafe fn contains_entry(&self, entry: *const NtSingleListEntry<E, L>) -> bool {
+        let mut current = self.next as *const NtSingleListEntry<E, L>;
+
+        while !current.is_null() {
+            if current == entry {
+                return true;
+            }
+
+            current = (*current).next;
+        }
+
+        false
+    }
+
     /// Returns the [`NtSingleListEntry`] for the given element.
     pub(crate) fn entry(element: &mut E) -> *mut NtSingleListEntry<E, L> {
+        // Catch an egregiously wrong `offset` of a hand-written `NtListElement` implementation
+        // before it leads to an out-of-bounds access.
+        debug_assert!(
+            mem::size_of::<E>()
+                .checked_sub(mem::size_of::<NtSingleListEntry<E, L>>())
+                .map_or(false, |max_offset| E::offset() <= max_offset),
+            "NtListElement::offset of {} returns {}, which is out of bounds for an element of {} bytes",
+            any::type_name::<E>(),
+            E::offset(),
+            mem::size_of::<E>()
+        );
+
         let element_ptr = element as *mut E;
 
         // This is the canonical implementation of `byte_add`
@@ -56,6 +90,42 @@ where
         entry.cast()
     }
 
+    /// Returns a pointer to the entry of the given element that is used for this list.
+    ///
+    /// This doesn't check whether `element` is part of any list.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn entry_of(element: &E) -> *const NtSingleListEntry<E, L> {
+        // This is the canonical implementation of `byte_add`
+        (element as *const E)
+            .cast::<u8>()
+            .wrapping_add(E::offset())
+            .cast()
+    }
+
+    /// Returns an iterator that removes all elements for which `f(&mut e)` returns `true` and
+    /// yields a pointer to each removed element in order.
+    ///
+    /// This is the counterpart of [`retain`](Self::retain) that hands out the removed elements
+    /// instead of discarding them.
+    /// Elements are only visited and removed while the iterator is consumed.
+    /// If it is dropped early, all elements that have not been visited yet stay in the list.
+    /// The link of every removed element is reset right before it is yielded, so the caller may
+    /// immediately push it to another list or deallocate it.
+    ///
+    /// Iterating all elements computes in *O*(*n*) time.
+    pub unsafe fn extract_if<F>(&mut self, f: F) -> ExtractIf<'_, E, L, F>
+    where
+        F: FnMut(&mut E) -> bool,
+    {
+        ExtractIf {
+            previous: (self as *mut Self).cast(),
+            current: self.next,
+            f,
+            phantom: PhantomData,
+        }
+    }
+
     /// Provides a reference to the first element, or `None` if the list is empty.
     ///
     /// This operation computes in *O*(*1*) time.
@@ -95,13 +165,48 @@ where
 
     /// Counts all elements and returns the length of the list.
     ///
+    /// Only the forward links are followed, so no reference to any element is formed.
+    ///
     /// This operation computes in *O*(*n*) time.
     pub unsafe fn len(&self) -> usize {
-        self.iter().count()
+        let mut current = self.next as *const NtSingleListEntry<E, L>;
+        let mut count = 0;
+
+        while !current.is_null() {
+            count += 1;
+            current = (*current).next;
+        }
+
+        count
+    }
+
+    /// Returns a pointer to the entry of the given element that is used for the doubly linked
+    /// list `L2`.
+    ///
+    /// This allows code that manages an element in both a singly and a doubly linked list to get
+    /// from the element to its entry of the other list.
+    /// `E` must implement both `NtListElement<L>` and `NtListElement<L2>`, which is the case if
+    /// the derived element structure has an entry field for each of the lists.
+    /// This doesn't check whether `element` is part of any list.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn list_entry_of<L2>(element: &E) -> *const NtListEntry<E, L2>
+    where
+        E: NtListElement<L2>,
+        L2: NtTypedList<T = NtList>,
+    {
+        NtListHead::<E, L2>::entry_of(element)
     }
 
     /// Removes the first element from the list and returns it, or `None` if the list is empty.
     ///
+    /// The returned reference borrows the list header, but it is not tied to the element storage,
+    /// which the list doesn't own.
+    /// It is up to the caller to not use the reference after the element has been freed, and to
+    /// not create any other reference to the element while this one is alive.
+    /// If the element is handed over to code that manages it separately, consider
+    /// [`pop_front_entry`](Self::pop_front_entry) instead.
+    ///
     /// This function substitutes [`PopEntryList`] of the Windows NT API.
     ///
     /// This operation computes in *O*(*1*) time.
@@ -115,6 +220,27 @@ where
         })
     }
 
+    /// Removes the first element from the list and returns a pointer to its entry, or `None` if
+    /// the list is empty.
+    ///
+    /// Unlike [`pop_front`](Self::pop_front), this doesn't form a reference to the element,
+    /// leaving it to the caller to handle the ownership of the detached element explicitly.
+    /// The link of the entry is reset, so the element may immediately be pushed to another list.
+    ///
+    /// This function substitutes [`PopEntryList`] of the Windows NT API.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    ///
+    /// [`PopEntryList`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-popentrylist
+    pub unsafe fn pop_front_entry(&mut self) -> Option<*mut NtSingleListEntry<E, L>> {
+        (!self.is_empty()).then(|| {
+            let entry = self.next;
+            self.next = (*entry).next;
+            (*entry).next = ptr::null_mut();
+            entry
+        })
+    }
+
     /// Appends an element to the front of the list.
     ///
     /// This function substitutes [`PushEntryList`] of the Windows NT API.
@@ -144,19 +270,41 @@ where
         let mut current = self.next;
 
         while !current.is_null() {
+            // Store the next pointer before handing out the element, just like
+            // `NtBoxingSingleListHead::retain` does.
+            let next = (*current).next;
             let element = NtSingleListEntry::containing_record_mut(current);
 
             if f(element) {
+                // Only a retained element becomes the predecessor of the next one.
+                // Removed elements are skipped over, so that a run of consecutive removals
+                // is unlinked by repeatedly updating the same `previous` entry.
                 previous = current;
             } else {
-                (*previous).next = (*current).next;
+                (*previous).next = next;
             }
 
-            current = (*current).next;
+            current = next;
         }
     }
 }
 
+impl<E, L> fmt::Debug for NtSingleListHead<E, L>
+where
+    E: NtListElement<L>,
+    L: NtTypedList<T = NtSingleList>,
+{
+    /// Formats only the forward link of the header.
+    ///
+    /// No element is dereferenced, so this is safe to call even when the elements of the list
+    /// may no longer be valid.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NtSingleListHead")
+            .field("next", &self.next)
+            .finish()
+    }
+}
+
 impl<E, L> Default for NtSingleListHead<E, L>
 where
     E: NtListElement<L>,
@@ -167,6 +315,62 @@ where
     }
 }
 
+/// Iterator over the elements removed from a singly linked list by a predicate.
+///
+/// This iterator is returned from the [`NtSingleListHead::extract_if`] function.
+pub struct ExtractIf<'a, E, L, F>
+where
+    E: NtListElement<L>,
+    L: NtTypedList<T = NtSingleList>,
+    F: FnMut(&mut E) -> bool,
+{
+    previous: *mut NtSingleListEntry<E, L>,
+    current: *mut NtSingleListEntry<E, L>,
+    f: F,
+    phantom: PhantomData<&'a mut NtSingleListHead<E, L>>,
+}
+
+impl<'a, E, L, F> Iterator for ExtractIf<'a, E, L, F>
+where
+    E: NtListElement<L>,
+    L: NtTypedList<T = NtSingleList>,
+    F: FnMut(&mut E) -> bool,
+{
+    type Item = *mut E;
+
+    fn next(&mut self) -> Option<*mut E> {
+        while !self.current.is_null() {
+            unsafe {
+                // Store the next pointer before handing out the element, just like `retain` does.
+                let entry = self.current;
+                let next = (*entry).next;
+                let element = NtSingleListEntry::containing_record_mut(entry);
+                self.current = next;
+
+                if (self.f)(element) {
+                    // Only a retained element becomes the predecessor of the next one, so that
+                    // a run of consecutive removals keeps updating the same `previous` entry.
+                    (*self.previous).next = next;
+                    (*entry).next = ptr::null_mut();
+                    return Some(element);
+                }
+
+                self.previous = entry;
+            }
+        }
+
+        None
+    }
+}
+
+impl<'a, E, L, F> FusedIterator for ExtractIf<'a, E, L, F>
+where
+    E: NtListElement<L>,
+    L: NtTypedList<T = NtSingleList>,
+    F: FnMut(&mut E) -> bool,
+{
+}
+
 /// Iterator over the elements of a singly linked list.
 ///
 /// This iterator is returned from the [`NtSingleListHead::iter`] and
@@ -178,6 +382,20 @@ pub struct Iter<'a, E: NtListElement<L>, L: NtTypedList<T = NtSingleList>> {
     phantom: PhantomData<&'a NtSingleListHead<E, L>>,
 }
 
+impl<'a, E, L> Clone for Iter<'a, E, L>
+where
+    E: NtListElement<L>,
+    L: NtTypedList<T = NtSingleList>,
+{
+    /// Returns an independent iterator at the same position, e.g. for looking ahead.
+    fn clone(&self) -> Self {
+        Self {
+            current: self.current,
+            phantom: PhantomData,
+        }
+    }
+}
+
 impl<'a, E, L> Iterator for Iter<'a, E, L>
 where
     E: NtListElement<L>,
@@ -255,16 +473,24 @@ where
     E: NtListElement<L>,
     L: NtTypedList<T = NtSingleList>,
 {
+    /// An entry that is not part of any list.
+    ///
+    /// This is the value returned by [`new`](Self::new), but usable in `const` and `static`
+    /// initializers.
+    pub const UNLINKED: Self = Self {
+        next: ptr::null_mut(),
+    };
+
     /// Allows the creation of an `NtSingleListEntry`, but leaves all fields uninitialized.
     ///
     /// Its fields are only initialized when an entry is pushed to a list.
     pub fn new() -> Self {
-        Self {
-            next: ptr::null_mut(),
-        }
+        Self::UNLINKED
     }
 
     pub(crate) unsafe fn containing_record<'a>(ptr: *const Self) -> &'a E {
+        Self::debug_check_element_address(ptr);
+
         // This is the canonical implementation of `byte_sub`
         let element_ptr = unsafe { ptr.cast::<u8>().sub(E::offset()).cast::<Self>() };
 
@@ -272,11 +498,52 @@ where
     }
 
     pub(crate) unsafe fn containing_record_mut<'a>(ptr: *mut Self) -> &'a mut E {
+        Self::debug_check_element_address(ptr);
+
         // This is the canonical implementation of `byte_sub`
         let element_ptr = unsafe { ptr.cast::<u8>().sub(E::offset()).cast::<Self>() };
 
         unsafe { &mut *element_ptr.cast() }
     }
+
+    /// Catches a wrong `offset` of a hand-written `NtListElement` implementation in debug
+    /// builds, before the element address computed from it is dereferenced.
+    fn debug_check_element_address(ptr: *const Self) {
+        let address = ptr as usize;
+
+        debug_assert!(
+            address >= E::offset(),
+            "NtListElement::offset of {} returns {}, which is larger than the entry address {:#x}",
+            any::type_name::<E>(),
+            E::offset(),
+            address
+        );
+        debug_assert!(
+            (address - E::offset()) % mem::align_of::<E>() == 0,
+            "NtListElement::offset of {} returns {}, which results in a misaligned element address {:#x}",
+            any::type_name::<E>(),
+            E::offset(),
+            address - E::offset()
+        );
+    }
+}
+
+impl<E, L> Clone for NtSingleListEntry<E, L>
+where
+    E: NtListElement<L>,
+    L: NtTypedList<T = NtSingleList>,
+{
+    /// Returns a new entry that is not part of any list, just like [`NtSingleListEntry::new`].
+    ///
+    /// Copying the links instead would make the clone point into the list of the original
+    /// entry, although the list doesn't point back to it.
+    /// Pushing or removing the clone would then corrupt that list.
+    ///
+    /// This allows deriving [`Clone`] for element structures, with every clone starting out
+    /// unlinked.
+    fn clone(&self) -> Self {
+        Self::new()
+    }
 }
 
 impl<E, L> Default for NtSingleListEntry<E, L>
@@ -288,3 +555,277 @@ where
         Self::new()
     }
 }
+
+impl<E, L> Eq for NtSingleListEntry<E, L>
+where
+    E: NtListElement<L>,
+    L: NtTypedList<T = NtSingleList>,
+{
+}
+
+impl<E, L> PartialEq for NtSingleListEntry<E, L>
+where
+    E: NtListElement<L>,
+    L: NtTypedList<T = NtSingleList>,
+{
+    /// Returns `true` if both entries have the same forward link.
+    ///
+    /// This only compares the link state of the entries, e.g. to verify that a list has been
+    /// linked as expected.
+    /// It says nothing about the equality of the elements containing the entries.
+    fn eq(&self, other: &Self) -> bool {
+        self.next == other.next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(NtSingleList)]
+    enum MyList {}
+
+    #[derive(Default, NtListElement)]
+    #[repr(C)]
+    struct MyElement {
+        value: i32,
+        entry: NtSingleListEntry<Self, MyList>,
+    }
+
+    impl MyElement {
+        fn new(value: i32) -> Self {
+            Self {
+                value,
+                ..Default::default()
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "out of bounds for an element")]
+    fn test_wrong_offset() {
+        #[repr(C)]
+        struct WrongElement {
+            entry: NtSingleListEntry<Self, MyList>,
+        }
+
+        // A hand-written implementation with an obviously wrong offset.
+        unsafe impl NtListElement<MyList> for WrongElement {
+            fn offset() -> usize {
+                1000000
+            }
+        }
+
+        let mut element = WrongElement {
+            entry: NtSingleListEntry::new(),
+        };
+        let mut list = NtSingleListHead::<WrongElement, MyList>::new();
+        unsafe { list.push_front(&mut element) };
+    }
+
+    #[test]
+    fn test_clone_entry() {
+        #[derive(Clone, NtListElement)]
+        #[repr(C)]
+        struct CloneableElement {
+            value: i32,
+            entry: NtSingleListEntry<Self, MyList>,
+        }
+
+        let mut element = CloneableElement {
+            value: 1,
+            entry: NtSingleListEntry::new(),
+        };
+        let mut other = CloneableElement {
+            value: 2,
+            entry: NtSingleListEntry::new(),
+        };
+        let mut list = NtSingleListHead::<CloneableElement, MyList>::new();
+
+        unsafe {
+            list.push_front(&mut other);
+            list.push_front(&mut element);
+        }
+
+        let clone = element.clone();
+        assert_eq!(clone.value, 1);
+        assert!(clone.entry.next.is_null());
+        assert!(!element.entry.next.is_null());
+    }
+
+    #[test]
+    fn test_entry_eq() {
+        let mut elements = [0, 1].map(MyElement::new);
+        assert!(elements[0].entry == elements[1].entry);
+
+        let mut list = NtSingleListHead::<MyElement, MyList>::new();
+
+        // Both elements are the last one of a list and therefore have the same null link.
+        unsafe { list.push_front(&mut elements[0]) };
+        assert!(elements[0].entry == elements[1].entry);
+
+        unsafe { list.push_front(&mut elements[1]) };
+        assert!(elements[0].entry != elements[1].entry);
+    }
+
+    #[test]
+    fn test_extract_if_consecutive() {
+        let mut elements = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9].map(MyElement::new);
+        let mut list = NtSingleListHead::<MyElement, MyList>::new();
+
+        for element in elements.iter_mut().rev() {
+            unsafe { list.push_front(element) };
+        }
+
+        // Extract the first element, a run in the middle, and a run at the end of the list.
+        let mut extracted = [0; 7];
+        let mut count = 0;
+        let iter = unsafe { list.extract_if(|element| matches!(element.value, 0 | 2..=4 | 7..=9)) };
+
+        for element in iter {
+            unsafe {
+                assert!((*element).entry.next.is_null());
+                extracted[count] = (*element).value;
+            }
+            count += 1;
+        }
+
+        assert_eq!(count, 7);
+        assert_eq!(extracted, [0, 2, 3, 4, 7, 8, 9]);
+        assert_eq!(unsafe { list.len() }, 3);
+
+        for (i, element) in [1, 5, 6].into_iter().zip(unsafe { list.iter() }) {
+            assert_eq!(i, element.value);
+        }
+
+        // Dropping the iterator early keeps all elements that have not been visited.
+        {
+            let mut iter = unsafe { list.extract_if(|_| true) };
+            assert_eq!(unsafe { (*iter.next().unwrap()).value }, 1);
+        }
+
+        assert_eq!(unsafe { list.len() }, 2);
+        assert_eq!(unsafe { list.front() }.unwrap().value, 5);
+    }
+
+    #[test]
+    fn test_iter_clone() {
+        let mut elements = [0, 1, 2].map(MyElement::new);
+        let mut list = NtSingleListHead::<MyElement, MyList>::new();
+
+        for element in elements.iter_mut().rev() {
+            unsafe { list.push_front(element) };
+        }
+
+        let mut iter = unsafe { list.iter() };
+        iter.next();
+
+        // Advancing the clone must not affect the original iterator.
+        let lookahead = iter.clone();
+        assert!(lookahead.map(|element| element.value).eq([1, 2]));
+        assert!(iter.map(|element| element.value).eq([1, 2]));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "misaligned element address")]
+    fn test_misaligned_offset() {
+        #[repr(C)]
+        struct WrongElement {
+            value: u64,
+            entry: NtSingleListEntry<Self, MyList>,
+        }
+
+        // A hand-written implementation with an offset into the middle of `value`.
+        unsafe impl NtListElement<MyList> for WrongElement {
+            fn offset() -> usize {
+                1
+            }
+        }
+
+        let element = WrongElement {
+            value: 0,
+            entry: NtSingleListEntry::new(),
+        };
+
+        // The correct entry address with a wrong offset yields a misaligned element address.
+        let entry = &element.entry as *const NtSingleListEntry<WrongElement, MyList>;
+        let _ = unsafe { NtSingleListEntry::containing_record(entry) };
+        let _ = element.value;
+    }
+
+    #[test]
+    fn test_pop_front_entry() {
+        let mut elements = [0, 1].map(MyElement::new);
+        let mut list = NtSingleListHead::<MyElement, MyList>::new();
+
+        for element in elements.iter_mut().rev() {
+            unsafe { list.push_front(element) };
+        }
+
+        let entry = unsafe { list.pop_front_entry() }.unwrap();
+        assert!(ptr::eq(entry, &elements[0].entry));
+        assert!(elements[0].entry.next.is_null());
+
+        let entry = unsafe { list.pop_front_entry() }.unwrap();
+        assert!(ptr::eq(entry, &elements[1].entry));
+
+        assert!(list.is_empty());
+        assert!(unsafe { list.pop_front_entry() }.is_none());
+    }
+
+    #[test]
+    fn test_retain_consecutive() {
+        let mut elements = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9].map(MyElement::new);
+        let mut list = NtSingleListHead::<MyElement, MyList>::new();
+
+        for element in elements.iter_mut().rev() {
+            unsafe { list.push_front(element) };
+        }
+
+        // Remove the first element, a run in the middle, and a run at the end of the list.
+        unsafe { list.retain(|element| !matches!(element.value, 0 | 2..=4 | 7..=9)) };
+
+        assert_eq!(unsafe { list.len() }, 3);
+
+        for (i, element) in [1, 5, 6].into_iter().zip(unsafe { list.iter() }) {
+            assert_eq!(i, element.value);
+        }
+
+        // Remove all remaining elements in one go.
+        unsafe { list.retain(|_| false) };
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_unlinked_static_element() {
+        static mut ELEMENT: MyElement = MyElement {
+            value: 42,
+            entry: NtSingleListEntry::UNLINKED,
+        };
+
+        let mut list = NtSingleListHead::<MyElement, MyList>::new();
+        unsafe { list.push_front(&mut *ptr::addr_of_mut!(ELEMENT)) };
+
+        assert_eq!(unsafe { list.len() }, 1);
+        assert_eq!(unsafe { list.front() }.unwrap().value, 42);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_debug() {
+        use alloc::format;
+
+        let mut element = MyElement::new(0);
+        let mut list = NtSingleListHead::<MyElement, MyList>::new();
+        assert_eq!(format!("{:?}", list), "NtSingleListHead { next: 0x0 }");
+
+        // The header must only print its own link.
+        unsafe { list.push_front(&mut element) };
+        assert_eq!(
+            format!("{:?}", list),
+            format!("NtSingleListHead {{ next: {:?} }}", list.next)
+        );
+    }
+}
@@ -1,12 +1,25 @@
 // Copyright 2022 Colin Finck <colin@reactos.org>
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
+use core::cmp::Ordering;
+use core::fmt;
+#[cfg(feature = "std")]
+use core::hash::Hash;
+use core::iter::{Cloned, Enumerate, FusedIterator};
+use core::marker::PhantomData;
+use core::mem;
 use core::ptr;
 
 use alloc::boxed::Box;
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
 
 use super::base::{Iter, IterMut, NtSingleListEntry, NtSingleListHead};
 use super::traits::NtSingleList;
+use crate::boxing::try_box;
+use crate::list::Decision;
 use crate::traits::{NtBoxedListElement, NtListElement, NtTypedList};
 
 /// A variant of [`NtSingleListHead`] that boxes every element on insertion.
@@ -40,32 +53,395 @@ where
         Self(NtSingleListHead::<E, L>::new())
     }
 
+    /// Creates a new singly linked list that takes ownership of the null-terminated chain of
+    /// entries starting at `first`.
+    ///
+    /// This allows a list built by foreign code to be cleaned up by Rust.
+    /// A null `first` results in an empty list.
+    ///
+    /// # Safety
+    ///
+    /// Every element of the chain must have been allocated in a way that is compatible with
+    /// [`Box`], i.e. via the global allocator of this program with the layout of `E`.
+    /// Memory allocated in any other way (e.g. via `ExAllocatePool`, `HeapAlloc`, or `malloc`)
+    /// must NOT be passed to this function, as the list deallocates every element through
+    /// [`Box`] when it is cleared or dropped.
+    ///
+    /// Additionally, every element must be a valid and initialized `E`, the chain must not
+    /// contain cycles, and no other code must access the chain afterwards.
+    pub unsafe fn adopt_chain(first: *mut NtSingleListEntry<E, L>) -> Self {
+        let mut list = Self::new();
+        list.0.next = first;
+        list
+    }
+
+    /// Moves all elements from `other` to the end of the list, given the entry of the last
+    /// element of this list, and returns the entry of the new last element.
+    ///
+    /// Unlike [`append_returning_tail`](Self::append_returning_tail), this doesn't traverse this
+    /// list to find its last element, only `other`.
+    /// Chaining many appends by passing the returned pointer back in as `tail` therefore
+    /// computes in *O*(*m*) time in total, where *m* is the total number of appended elements.
+    ///
+    /// If this list is empty after the operation, a null pointer is returned.
+    ///
+    /// This operation computes in *O*(*m*) time, where *m* is the length of `other`.
+    ///
+    /// # Safety
+    ///
+    /// `tail` must be a null pointer if this list is empty, and otherwise point to the entry of
+    /// its last element.
+    /// This is checked in debug builds.
+    /// A pointer returned by this function or [`append_returning_tail`](Self::append_returning_tail)
+    /// fulfills this contract until the list is modified by any other function.
+    pub unsafe fn append_after_tail(
+        &mut self,
+        tail: *mut NtSingleListEntry<E, L>,
+        mut other: Self,
+    ) -> *mut NtSingleListEntry<E, L> {
+        debug_assert!(
+            if tail.is_null() {
+                self.is_empty()
+            } else {
+                (*tail).next.is_null() && self.0.contains_entry(tail)
+            },
+            "tail is not the last entry of this list"
+        );
+
+        if other.is_empty() {
+            return tail;
+        }
+
+        let first = other.0.next;
+        let mut last = first;
+        while !(*last).next.is_null() {
+            last = (*last).next;
+        }
+
+        if tail.is_null() {
+            self.0.next = first;
+        } else {
+            (*tail).next = first;
+        }
+
+        // `other` must not deallocate the elements, which are now owned by `self`.
+        other.0.clear();
+
+        last
+    }
+
+    /// Moves all elements from `other` to the end of the list and returns the entry of the new
+    /// last element.
+    ///
+    /// A singly linked list has no link to its last element, so this traverses both lists.
+    /// Pass the returned pointer to [`append_after_tail`](Self::append_after_tail) to append
+    /// further lists without traversing this list again.
+    ///
+    /// If this list is empty after the operation, a null pointer is returned.
+    ///
+    /// This operation computes in *O*(*n* + *m*) time, where *m* is the length of `other`.
+    pub fn append_returning_tail(&mut self, other: Self) -> *mut NtSingleListEntry<E, L> {
+        let mut tail = ptr::null_mut();
+        let mut current = self.0.next;
+
+        while !current.is_null() {
+            tail = current;
+            current = unsafe { (*current).next };
+        }
+
+        unsafe { self.append_after_tail(tail, other) }
+    }
+
+    /// Returns a vector of mutable references to all elements of the list, in order.
+    ///
+    /// This provides indexed access to the elements for algorithms that need it.
+    /// Every reference points to a different element, and the vector borrows the list
+    /// mutably for as long as it exists.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn as_mut_vec(&mut self) -> Vec<&mut E> {
+        self.iter_mut().collect()
+    }
+
+    /// Returns a vector of references to all elements of the list, in order.
+    ///
+    /// This provides indexed access to the elements for algorithms that need it.
+    /// The vector borrows the list for as long as it exists.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn as_ref_vec(&self) -> Vec<&E> {
+        self.iter().collect()
+    }
+
     /// Removes all elements from the list, deallocating their memory.
     ///
+    /// Elements are dropped in list order, from the first to the last one.
+    /// The same order applies when the list itself is dropped.
+    ///
+    /// If the `Drop` handler of an element panics, the memory of that element is still
+    /// deallocated, and all elements after it stay in the list, which is still valid.
+    /// Call `clear` again, e.g. from the handler that caught the panic via `catch_unwind`, to
+    /// drop them as well.
+    ///
     /// Unlike [`NtSingleListHead::clear`], this operation computes in *O*(*n*) time, because it
     /// needs to traverse all elements to deallocate them.
     pub fn clear(&mut self) {
-        // Get the link to the first element before it's being reset.
-        let mut current = self.0.next;
+        self.clear_with(|_| {})
+    }
+
+    /// Removes all elements from the list, calling `f` on each element right before it is
+    /// dropped and deallocated.
+    ///
+    /// This allows releasing per-element resources in list order, from the first to the last
+    /// element, while the memory of each element is still valid.
+    /// `f` is only invoked by this function and not when the list is dropped or elements are
+    /// removed in any other way.
+    ///
+    /// If `f` panics, the element passed to it has already been removed from the list and is
+    /// leaked.
+    /// If the `Drop` handler of an element panics, the memory of that element is still
+    /// deallocated.
+    /// In both cases, all remaining elements stay in the list, just like for a panic in
+    /// [`clear`](Self::clear).
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn clear_with<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut E),
+    {
+        self.debug_assert_no_cycles();
 
-        // Make the list appear empty before deallocating any element.
-        // By doing this here and not at the very end, we guard against the following scenario:
+        // Unlink every element before deallocating it.
+        // By doing this for each element and not for the entire list at the very end, we guard
+        // against the following scenario:
         //
         // 1. We deallocate an element.
         // 2. The `Drop` handler of that element is called and panics.
         // 3. Consequently, the `Drop` handler of `NtBoxingSingleListHead` is called and removes all elements.
         // 4. While removing elements, the just dropped element is dropped again.
         //
-        // By clearing the list at the beginning, the `Drop` handler of `NtBoxingSingleListHead` won't find any
-        // elements, and thereby it won't drop any elements.
-        self.0.clear();
+        // As the element is no longer part of the list when it is dropped, the `Drop` handler of
+        // `NtBoxingSingleListHead` only finds the remaining elements, which still form a valid list.
+        loop {
+            let current = self.0.next;
+            if current.is_null() {
+                break;
+            }
 
-        // Traverse the list in the old-fashioned way and deallocate each element.
-        while !current.is_null() {
             unsafe {
-                let next = (*current).next;
+                self.0.next = (*current).next;
+
                 let element = NtSingleListEntry::<E, L>::containing_record_mut(current);
+                f(element);
                 drop(Box::from_raw(element));
+            }
+        }
+    }
+
+    /// Returns an iterator yielding clones of each element of the list, in order.
+    ///
+    /// This is equivalent to `iter().cloned()`.
+    /// Every element is only cloned when it is yielded.
+    pub fn cloned(&self) -> Cloned<Iter<'_, E, L>>
+    where
+        E: Clone,
+    {
+        self.iter().cloned()
+    }
+
+    /// Appends clones of all elements of the list to `buf`, in order.
+    ///
+    /// Existing elements of `buf` are kept, and its capacity is reused.
+    /// This avoids allocating a new [`Vec`] for every snapshot of the list, e.g. in a polling loop.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn collect_into(&self, buf: &mut Vec<E>)
+    where
+        E: Clone,
+    {
+        buf.extend(self.iter().cloned());
+    }
+
+    /// Appends references to all elements of the list to `buf`, in order.
+    ///
+    /// Just like [`collect_into`](Self::collect_into), this reuses the capacity of `buf`.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn collect_refs_into<'a>(&'a self, buf: &mut Vec<&'a E>) {
+        buf.extend(self.iter());
+    }
+
+    /// Returns `true` if the element at the given address is part of this list.
+    ///
+    /// Unlike a comparison of element values, this checks for the identity of `element`.
+    /// `element` does not need to point to a valid element. It is only compared against the
+    /// addresses of all list elements and never dereferenced.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn contains_ptr(&self, element: *const E) -> bool {
+        // This is the canonical implementation of `wrapping_byte_add`
+        let entry = element
+            .cast::<u8>()
+            .wrapping_add(E::offset())
+            .cast::<NtSingleListEntry<E, L>>();
+
+        unsafe { self.0.contains_entry(entry) }
+    }
+
+    /// Compares the elements of this list with those of `other` using the predicate `f`.
+    ///
+    /// Returns `true` if both lists have the same length and `f` returns `true` for every pair
+    /// of elements at the same position.
+    /// Comparison stops at the first pair for which `f` returns `false`.
+    ///
+    /// This is useful if `E` does not implement [`PartialEq`] or if some fields of the elements
+    /// shall be ignored.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn eq_by<F>(&self, other: &Self, mut f: F) -> bool
+    where
+        F: FnMut(&E, &E) -> bool,
+    {
+        let mut iter = self.iter();
+        let mut other_iter = other.iter();
+
+        loop {
+            match (iter.next(), other_iter.next()) {
+                (Some(element), Some(other_element)) => {
+                    if !f(element, other_element) {
+                        return false;
+                    }
+                }
+                (None, None) => return true,
+                _ => return false,
+            }
+        }
+    }
+
+    /// Appends all elements yielded by `iter` to the end of the list and returns how many
+    /// elements have been appended.
+    ///
+    /// As a singly linked list has no link to its last element, the list needs to be traversed
+    /// once to find it.
+    ///
+    /// This operation computes in *O*(*n* + *m*) time, where *m* is the number of elements
+    /// yielded by `iter`.
+    pub fn extend_count<I>(&mut self, iter: I) -> usize
+    where
+        I: IntoIterator<Item = E>,
+    {
+        let mut previous =
+            (&mut self.0 as *mut NtSingleListHead<E, L>).cast::<NtSingleListEntry<E, L>>();
+        let mut count = 0;
+
+        unsafe {
+            while !(*previous).next.is_null() {
+                previous = (*previous).next;
+            }
+        }
+
+        for element in iter.into_iter() {
+            // Link the elements by hand, just like the `FromIterator` implementation does.
+            unsafe {
+                let entry = NtSingleListHead::entry(Box::leak(Box::new(element)));
+
+                (*entry).next = ptr::null_mut();
+                (*previous).next = entry;
+
+                previous = entry;
+            }
+
+            count += 1;
+        }
+
+        count
+    }
+
+    /// Returns a reference to the first element matching the predicate, or `None` if no element
+    /// matches.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn find<P>(&self, mut p: P) -> Option<&E>
+    where
+        P: FnMut(&E) -> bool,
+    {
+        self.iter().find(|element| p(element))
+    }
+
+    /// Returns a mutable reference to the first element matching the predicate, or `None` if no
+    /// element matches.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn find_mut<P>(&mut self, mut p: P) -> Option<&mut E>
+    where
+        P: FnMut(&E) -> bool,
+    {
+        self.iter_mut().find(|element| p(element))
+    }
+
+    /// Returns a reference to the first element that is equal to an element before it, or `None`
+    /// if all elements are distinct.
+    ///
+    /// Every element is compared with all elements before it, so this only requires [`PartialEq`].
+    /// It is meant for validating invariants in tests and assertions, not for large lists.
+    ///
+    /// This operation computes in *O*(*n*²) time.
+    pub fn first_duplicate(&self) -> Option<&E>
+    where
+        E: PartialEq,
+    {
+        self.iter()
+            .enumerate()
+            .find(|(index, element)| self.iter().take(*index).any(|other| other == *element))
+            .map(|(_, element)| element)
+    }
+
+    /// Folds every element into an accumulator by applying `f`, in order, and returns the final
+    /// accumulator.
+    ///
+    /// This is equivalent to `iter().fold(init, f)`.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn fold<B, F>(&self, init: B, f: F) -> B
+    where
+        F: FnMut(B, &E) -> B,
+    {
+        self.iter().fold(init, f)
+    }
+
+    /// Calls `f` with an [`ElementCursor`] for every element in order.
+    ///
+    /// The [`ElementCursor`] provides mutable access to its element and can insert new elements
+    /// after it or remove it from the list.
+    /// The link to the next element is saved before `f` is called, and none of these operations
+    /// touch the saved element, so the traversal stays intact.
+    /// Newly inserted elements are not visited.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn for_each_with_cursor<F>(&mut self, mut f: F)
+    where
+        F: FnMut(ElementCursor<'_, E, L>),
+    {
+        let mut previous = (self as *mut Self).cast();
+        let mut current = self.0.next;
+
+        while !current.is_null() {
+            unsafe {
+                let next = (*current).next;
+
+                f(ElementCursor {
+                    previous,
+                    entry: current,
+                    insert_position: current,
+                    phantom: PhantomData,
+                });
+
+                // The current element may have been removed or followed by new elements.
+                // Either way, advance `previous` to the entry that now precedes `next`.
+                while (*previous).next != next {
+                    previous = (*previous).next;
+                }
+
                 current = next;
             }
         }
@@ -85,6 +461,57 @@ where
         unsafe { self.0.front_mut() }
     }
 
+    /// Returns `true` if any two elements of the list are equal.
+    ///
+    /// See [`first_duplicate`](Self::first_duplicate) for details.
+    ///
+    /// This operation computes in *O*(*n*²) time.
+    pub fn has_duplicates(&self) -> bool
+    where
+        E: PartialEq,
+    {
+        self.first_duplicate().is_some()
+    }
+
+    /// Builds a map from the key returned by `f` for each element to a reference to that element.
+    ///
+    /// The list is traversed once.
+    /// If several elements have the same key, the last one of them wins.
+    /// Use [`index_by_multi`](Self::index_by_multi) to keep all of them.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn index_by<K, F>(&self, mut f: F) -> HashMap<K, &E>
+    where
+        K: Eq + Hash,
+        F: FnMut(&E) -> K,
+    {
+        self.iter().map(|element| (f(element), element)).collect()
+    }
+
+    /// Builds a map from the key returned by `f` for each element to references to all elements
+    /// with that key, in list order.
+    ///
+    /// The list is traversed once.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn index_by_multi<K, F>(&self, mut f: F) -> HashMap<K, Vec<&E>>
+    where
+        K: Eq + Hash,
+        F: FnMut(&E) -> K,
+    {
+        let mut map = HashMap::<K, Vec<&E>>::new();
+
+        for element in self.iter() {
+            map.entry(f(element)).or_default().push(element);
+        }
+
+        map
+    }
+
     /// Returns `true` if the list is empty.
     ///
     /// This operation computes in *O*(*1*) time.
@@ -97,11 +524,40 @@ where
         unsafe { self.0.iter() }
     }
 
+    /// Returns an iterator yielding the index and a reference to each element of the list.
+    ///
+    /// This is equivalent to `iter().enumerate()`.
+    pub fn iter_enumerated(&self) -> Enumerate<Iter<'_, E, L>> {
+        self.iter().enumerate()
+    }
+
+    /// Returns an iterator yielding the index and a mutable reference to each element of the
+    /// list.
+    ///
+    /// This is equivalent to `iter_mut().enumerate()`.
+    pub fn iter_enumerated_mut(&mut self) -> Enumerate<IterMut<'_, E, L>> {
+        self.iter_mut().enumerate()
+    }
+
     /// Returns an iterator yielding mutable references to each element of the list.
     pub fn iter_mut(&mut self) -> IterMut<E, L> {
         unsafe { self.0.iter_mut() }
     }
 
+    /// Returns an iterator yielding references to every `step`-th element of the list, starting
+    /// with the first one.
+    ///
+    /// This is equivalent to `iter().step_by(step)`.
+    /// Skipped elements still need to be traversed, so iterating all of them computes in
+    /// *O*(*n*) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is 0.
+    pub fn iter_step_by(&self, step: usize) -> impl Iterator<Item = &E> + '_ {
+        self.iter().step_by(step)
+    }
+
     /// Counts all elements and returns the length of the list.
     ///
     /// This operation computes in *O*(*n*) time.
@@ -109,156 +565,1168 @@ where
         unsafe { self.0.len() }
     }
 
-    /// Removes the first element from the list and returns it, or `None` if the list is empty.
+    /// Projects every element into a value of type `T` via `f` and returns all values in a
+    /// [`Vec`], in order.
     ///
-    /// This function substitutes [`PopEntryList`] of the Windows NT API.
+    /// This is equivalent to `iter().map(f).collect()`.
     ///
-    /// This operation computes in *O*(*1*) time.
+    /// This operation computes in *O*(*n*) time.
+    pub fn map_to_vec<T, F>(&self, f: F) -> Vec<T>
+    where
+        F: FnMut(&E) -> T,
+    {
+        self.iter().map(f).collect()
+    }
+
+    /// Projects every element into a value of type `T` via `f` and returns all values in a
+    /// [`Vec`], in order.
     ///
-    /// [`PopEntryList`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-popentrylist
-    pub fn pop_front(&mut self) -> Option<Box<E>> {
-        unsafe { self.0.pop_front().map(|element| Box::from_raw(element)) }
+    /// Unlike [`map_to_vec`](Self::map_to_vec), `f` gets mutable access to each element, e.g. to
+    /// move data out of it during the projection.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn map_to_vec_mut<T, F>(&mut self, f: F) -> Vec<T>
+    where
+        F: FnMut(&mut E) -> T,
+    {
+        self.iter_mut().map(f).collect()
     }
 
-    /// Appends an element to the front of the list.
+    /// Merges all elements of the sorted list `other` into this sorted list, using the comparator
+    /// function `f` to keep the result sorted.
     ///
-    /// This function substitutes [`PushEntryList`] of the Windows NT API.
+    /// Both lists must already be sorted according to `f`.
+    /// The merge is stable: Of two equal elements, the one from this list comes first.
+    /// No element is moved in memory, only the links are updated.
     ///
-    /// This operation computes in *O*(*1*) time.
+    /// If `f` panics, some elements of either list may be leaked.
     ///
-    /// [`PushEntryList`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-pushentrylist
-    pub fn push_front(&mut self, element: E) {
-        let boxed_element = Box::new(element);
-        unsafe { self.0.push_front(Box::leak(boxed_element)) }
+    /// This operation computes in *O*(*n* + *m*) time, where *m* is the length of `other`.
+    pub fn merge_by<F>(&mut self, mut other: Self, mut f: F)
+    where
+        F: FnMut(&E, &E) -> Ordering,
+    {
+        let mut current = self.0.next;
+        let mut other_current = other.0.next;
+
+        // `other` must not deallocate its elements when it is dropped.
+        other.0.clear();
+
+        let mut previous =
+            (&mut self.0 as *mut NtSingleListHead<E, L>).cast::<NtSingleListEntry<E, L>>();
+
+        unsafe {
+            while !current.is_null() && !other_current.is_null() {
+                let element = NtSingleListEntry::containing_record(current);
+                let other_element = NtSingleListEntry::containing_record(other_current);
+
+                // Only take the element of `other` if it is strictly less to keep the merge stable.
+                if f(other_element, element) == Ordering::Less {
+                    (*previous).next = other_current;
+                    previous = other_current;
+                    other_current = (*other_current).next;
+                } else {
+                    (*previous).next = current;
+                    previous = current;
+                    current = (*current).next;
+                }
+            }
+
+            // Attach the remaining elements of whichever list is left.
+            (*previous).next = if current.is_null() {
+                other_current
+            } else {
+                current
+            };
+        }
     }
 
-    /// Retains only the elements specified by the predicate, passing a mutable reference to it.
+    /// Splits the list into a list of all elements for which `p` returns `true` and a list of
+    /// all other elements, keeping their order.
     ///
-    /// In other words, remove all elements `e` for which `f(&mut e)` returns `false`.
-    /// This method operates in place, visiting each element exactly once in the original order,
-    /// and preserves the order of the retained elements.
+    /// The elements are only relinked and not moved in memory.
     ///
     /// This operation computes in *O*(*n*) time.
-    pub fn retain<F>(&mut self, mut f: F)
+    pub fn partition<P>(mut self, mut p: P) -> (Self, Self)
     where
-        F: FnMut(&mut E) -> bool,
+        P: FnMut(&E) -> bool,
     {
-        let mut previous = (self as *mut Self).cast();
-        let mut current = self.0.next;
+        let mut matching = Self::new();
+        let mut rest = Self::new();
+        let mut matching_tail = (&mut matching as *mut Self).cast::<NtSingleListEntry<E, L>>();
+        let mut rest_tail = (&mut rest as *mut Self).cast::<NtSingleListEntry<E, L>>();
 
-        while !current.is_null() {
+        while !self.0.next.is_null() {
             unsafe {
-                // Note: we can soundly store the next pointer ahead of time,
-                // since the only methods that can modify the next pointer are
-                // `NtSingleListEntry::{push,pop}_front`, and both of those
-                // are unsafe.
-                let next = (*current).next;
-                let element = NtSingleListEntry::containing_record_mut(current);
+                let current = self.0.next;
 
-                if f(element) {
-                    previous = current;
-                    current = next;
+                // Only unlink the element after `p` has returned, so that all lists stay
+                // consistent if it panics.
+                let is_match = p(NtSingleListEntry::containing_record(current));
+                self.0.next = (*current).next;
+                (*current).next = ptr::null_mut();
+
+                let tail = if is_match {
+                    &mut matching_tail
                 } else {
-                    (*previous).next = next;
-                    current = next;
-                    drop(Box::from_raw(element));
-                }
+                    &mut rest_tail
+                };
+
+                (**tail).next = current;
+                *tail = current;
             }
         }
+
+        (matching, rest)
     }
-}
 
-impl<E, L> Default for NtBoxingSingleListHead<E, L>
-where
-    E: NtBoxedListElement<L = L> + NtListElement<L>,
-    L: NtTypedList<T = NtSingleList>,
-{
-    fn default() -> Self {
-        Self::new()
+    /// Removes the first element from the list and returns it, or `None` if the list is empty.
+    ///
+    /// This function substitutes [`PopEntryList`] of the Windows NT API.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    ///
+    /// [`PopEntryList`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-popentrylist
+    pub fn pop_front(&mut self) -> Option<Box<E>> {
+        unsafe { self.0.pop_front().map(|element| Box::from_raw(element)) }
+    }
+
+    /// Removes the first element from the list and returns it if the predicate `p` returns
+    /// `true` for it.
+    ///
+    /// Returns `None` if the list is empty or `p` returns `false`, in which case the element
+    /// stays in the list.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn pop_front_if<P>(&mut self, p: P) -> Option<Box<E>>
+    where
+        P: FnOnce(&E) -> bool,
+    {
+        if p(self.front()?) {
+            self.pop_front()
+        } else {
+            None
+        }
+    }
+
+    /// Removes the first `n` elements from the list and returns them as a new list.
+    ///
+    /// If the list has fewer than `n` elements, all of them are moved.
+    /// No element is moved in memory, only the links are updated.
+    ///
+    /// This operation computes in *O*(*min*(*n*, *len*)) time.
+    pub fn pop_n_front(&mut self, n: usize) -> Self {
+        let mut popped = Self::new();
+
+        if n == 0 || self.is_empty() {
+            return popped;
+        }
+
+        unsafe {
+            // Find the last element to move.
+            let mut last_popped = self.0.next;
+
+            for _ in 1..n {
+                let next = (*last_popped).next;
+                if next.is_null() {
+                    break;
+                }
+
+                last_popped = next;
+            }
+
+            popped.0.next = self.0.next;
+            self.0.next = (*last_popped).next;
+            (*last_popped).next = ptr::null_mut();
+        }
+
+        popped
+    }
+
+    /// Inserts all elements of `iter` at the front of the list, preserving their order.
+    ///
+    /// The first element of `iter` becomes the new first element of the list, followed by the
+    /// remaining elements of `iter` and then by all elements that were previously in the list.
+    /// Calling [`push_front`](Self::push_front) for each element would insert them in reverse
+    /// order instead.
+    ///
+    /// The elements are linked to each other first and then put in front of the list at once.
+    /// If `iter` panics, the list stays unchanged.
+    ///
+    /// This operation computes in *O*(*k*) time, where *k* is the number of elements of `iter`.
+    pub fn prepend_iter<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = E>,
+    {
+        let mut batch = iter.into_iter().collect::<Self>();
+
+        if batch.is_empty() {
+            return;
+        }
+
+        unsafe {
+            let mut last = batch.0.next;
+            while !(*last).next.is_null() {
+                last = (*last).next;
+            }
+
+            (*last).next = self.0.next;
+            self.0.next = batch.0.next;
+        }
+
+        // `batch` must not deallocate the elements, which are now owned by `self`.
+        batch.0.clear();
+    }
+
+    /// Appends an element to the front of the list.
+    ///
+    /// This function substitutes [`PushEntryList`] of the Windows NT API.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    ///
+    /// [`PushEntryList`]: https://docs.microsoft.com/en-us/windows-hardware/drivers/ddi/wdm/nf-wdm-pushentrylist
+    pub fn push_front(&mut self, element: E) {
+        let boxed_element = Box::new(element);
+        unsafe { self.0.push_front(Box::leak(boxed_element)) }
+    }
+
+    /// Retains only the elements specified by the predicate, passing a mutable reference to it.
+    ///
+    /// In other words, remove all elements `e` for which `f(&mut e)` returns `false`.
+    /// This method operates in place, visiting each element exactly once in the original order,
+    /// and preserves the order of the retained elements.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut E) -> bool,
+    {
+        let mut previous = (self as *mut Self).cast();
+        let mut current = self.0.next;
+
+        while !current.is_null() {
+            unsafe {
+                // Note: we can soundly store the next pointer ahead of time,
+                // since the only methods that can modify the next pointer are
+                // `NtSingleListEntry::{push,pop}_front`, and both of those
+                // are unsafe.
+                let next = (*current).next;
+                let element = NtSingleListEntry::containing_record_mut(current);
+
+                if f(element) {
+                    previous = current;
+                    current = next;
+                } else {
+                    (*previous).next = next;
+                    current = next;
+                    drop(Box::from_raw(element));
+                }
+            }
+        }
+    }
+
+    /// Retains only the elements specified by the closure, stopping early if requested.
+    ///
+    /// `f` is called for every element in order and decides whether to keep or remove it.
+    /// If it returns [`Decision::StopKeepingRest`], the traversal stops, and the current
+    /// element and all elements after it are kept without being visited.
+    /// This avoids scanning the entire list, e.g. if the list is sorted and all elements past a
+    /// threshold shall be kept.
+    ///
+    /// This operation computes in *O*(*k*) time, where *k* is the number of visited elements.
+    pub fn retain_until<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut E) -> Decision,
+    {
+        let mut previous = (self as *mut Self).cast();
+        let mut current = self.0.next;
+
+        while !current.is_null() {
+            unsafe {
+                // Store the next pointer before the current element may be deallocated,
+                // just like `retain` does.
+                let next = (*current).next;
+                let element = NtSingleListEntry::containing_record_mut(current);
+
+                match f(element) {
+                    Decision::Keep => previous = current,
+                    Decision::Remove => {
+                        (*previous).next = next;
+                        drop(Box::from_raw(element));
+                    }
+                    Decision::StopKeepingRest => break,
+                }
+
+                current = next;
+            }
+        }
+    }
+
+    /// Returns an iterator yielding references to each element of the list in reverse order.
+    ///
+    /// A singly linked list can only be traversed from front to back.
+    /// Therefore, this function first collects pointers to all entries into a [`Vec`], which
+    /// takes *O*(*n*) time and memory once.
+    /// Afterwards, every iteration step computes in *O*(*1*) time.
+    pub fn rev_iter(&self) -> RevIter<'_, E, L> {
+        let mut entries = Vec::new();
+        let mut current = self.0.next as *const NtSingleListEntry<E, L>;
+
+        while !current.is_null() {
+            entries.push(current);
+            current = unsafe { (*current).next };
+        }
+
+        RevIter {
+            entries,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the last element matching the predicate, or `None` if no element
+    /// matches.
+    ///
+    /// A singly linked list can only be traversed from front to back.
+    /// Therefore, all elements are checked in a single forward pass, remembering the most recent
+    /// match, and no memory is allocated.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn rfind<P>(&self, mut p: P) -> Option<&E>
+    where
+        P: FnMut(&E) -> bool,
+    {
+        self.iter().filter(|element| p(element)).last()
+    }
+
+    /// Rotates the list in place such that the first `n` elements move to the end.
+    ///
+    /// `n` is taken modulo the length of the list, and rotating an empty list does nothing.
+    /// No element is moved in memory, only the links are updated.
+    ///
+    /// Unlike for a doubly linked list, there is no link to the last element and no stored
+    /// length, so the list is traversed once to find both, and once more up to the `n`-th
+    /// element.
+    ///
+    /// This operation computes in *O*(*n*) time, where *n* is the length of the list.
+    pub fn rotate_left(&mut self, n: usize) {
+        self.rotate_left_by(|len| n % len);
+    }
+
+    /// Moves the first elements to the end of the list, where `split_index` computes their
+    /// number from the length of the list, which is never 0.
+    fn rotate_left_by<F>(&mut self, split_index: F)
+    where
+        F: FnOnce(usize) -> usize,
+    {
+        let mut last = self.0.next;
+        if last.is_null() {
+            return;
+        }
+
+        unsafe {
+            // Find the length and the last element of the list in a single pass.
+            let mut len = 1;
+            while !(*last).next.is_null() {
+                last = (*last).next;
+                len += 1;
+            }
+
+            let n = split_index(len);
+            if n == 0 {
+                return;
+            }
+
+            // Find the last element to move.
+            let mut last_moved = self.0.next;
+            for _ in 1..n {
+                last_moved = (*last_moved).next;
+            }
+
+            // Move the elements up to `last_moved` after `last`.
+            (*last).next = self.0.next;
+            self.0.next = (*last_moved).next;
+            (*last_moved).next = ptr::null_mut();
+        }
+    }
+
+    /// Rotates the list in place such that the last `n` elements move to the front.
+    ///
+    /// This is the same as calling [`rotate_left`](Self::rotate_left) with the length of the
+    /// list minus `n`, but without traversing the list an additional time to get its length.
+    /// `n` is taken modulo the length of the list, and rotating an empty list does nothing.
+    ///
+    /// This operation computes in *O*(*n*) time, where *n* is the length of the list.
+    pub fn rotate_right(&mut self, n: usize) {
+        self.rotate_left_by(|len| (len - n % len) % len);
+    }
+
+    /// Searches for the last element matching the predicate and returns its index
+    /// (counted from the front), or `None` if no element matches.
+    ///
+    /// Just like [`rfind`](Self::rfind), this checks all elements in a single forward pass.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    pub fn rposition<P>(&self, mut p: P) -> Option<usize>
+    where
+        P: FnMut(&E) -> bool,
+    {
+        self.iter()
+            .enumerate()
+            .filter(|(_, element)| p(element))
+            .last()
+            .map(|(index, _)| index)
+    }
+
+    /// Removes the first element from the list and returns it together with a new list holding
+    /// all remaining elements.
+    ///
+    /// This is analogous to [`slice::split_first`].
+    /// If the list is empty, `None` is returned.
+    /// Afterwards, this list is always empty.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn split_first(&mut self) -> Option<(Box<E>, Self)> {
+        let first = self.pop_front()?;
+        let rest = mem::take(self);
+        Some((first, rest))
+    }
+
+    /// Exchanges all elements of this list with those of `other`.
+    ///
+    /// Unlike for a doubly linked list, no element points back to the list header, so only the
+    /// links of both headers need to be swapped.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn swap(&mut self, other: &mut Self) {
+        mem::swap(&mut self.0.next, &mut other.0.next);
+    }
+
+    /// Collects clones of all elements into a [`BTreeSet`], dropping duplicates.
+    ///
+    /// The order of the list is lost, as the set is ordered by the [`Ord`] implementation of `E`.
+    ///
+    /// This operation computes in *O*(*n* log *n*) time.
+    pub fn to_btree_set(&self) -> BTreeSet<E>
+    where
+        E: Clone + Ord,
+    {
+        self.iter().cloned().collect()
+    }
+
+    /// Collects clones of all elements into a [`HashSet`], dropping duplicates.
+    ///
+    /// The order of the list is lost.
+    ///
+    /// This operation computes in *O*(*n*) time.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn to_hash_set(&self) -> HashSet<E>
+    where
+        E: Clone + Eq + Hash,
+    {
+        self.iter().cloned().collect()
+    }
+
+    /// Appends an element to the front of the list, returning it back as `Err` if memory for it
+    /// could not be allocated.
+    ///
+    /// Unlike [`push_front`](Self::push_front), this function never panics or aborts on
+    /// allocation failure.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn try_push_front(&mut self, element: E) -> Result<(), E> {
+        let boxed_element = try_box(element)?;
+        unsafe { self.0.push_front(Box::leak(boxed_element)) }
+        Ok(())
+    }
+
+    /// Panics in debug builds if the elements form a cycle.
+    ///
+    /// A cycle can only be introduced via the `unsafe` API, e.g. [`adopt_chain`](Self::adopt_chain).
+    /// Without this check, deallocating all elements would loop forever and free elements twice.
+    /// Release builds skip the check to not traverse the list twice.
+    ///
+    /// Before panicking, the list is made empty and all elements are leaked.
+    /// This prevents a second panic when the list is dropped afterwards.
+    fn debug_assert_no_cycles(&mut self) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+
+        // Floyd's cycle detection: `fast` advances two elements for every element of `slow`.
+        // In a cycle, both eventually point to the same element.
+        let mut slow = self.0.next;
+        let mut fast = self.0.next;
+
+        unsafe {
+            loop {
+                for _ in 0..2 {
+                    if fast.is_null() {
+                        return;
+                    }
+
+                    fast = (*fast).next;
+                }
+
+                slow = (*slow).next;
+
+                if slow == fast {
+                    self.0.clear();
+                    panic!("the elements of the list form a cycle");
+                }
+            }
+        }
+    }
+}
+
+/// Concatenates all `lists` into a single list, in order, and returns it.
+///
+/// As a singly linked list has no link to its last element, the elements of every list except
+/// the last one need to be traversed to find the end of the chain.
+///
+/// This operation computes in *O*(*n*) time, where *n* is the total number of elements.
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn concat<E, L, I>(lists: I) -> NtBoxingSingleListHead<E, L>
+where
+    E: NtBoxedListElement<L = L> + NtListElement<L>,
+    L: NtTypedList<T = NtSingleList>,
+    I: IntoIterator<Item = NtBoxingSingleListHead<E, L>>,
+{
+    let mut result = NtBoxingSingleListHead::<E, L>::new();
+    let mut last = (&mut result.0 as *mut NtSingleListHead<E, L>).cast::<NtSingleListEntry<E, L>>();
+
+    for mut list in lists {
+        unsafe {
+            // Find the end of the chain built so far.
+            while !(*last).next.is_null() {
+                last = (*last).next;
+            }
+
+            // Move the elements of `list` to the end of the chain.
+            (*last).next = list.0.next;
+        }
+
+        list.0.clear();
+    }
+
+    result
+}
+
+impl<E, L> fmt::Debug for NtBoxingSingleListHead<E, L>
+where
+    E: NtBoxedListElement<L = L> + NtListElement<L> + fmt::Debug,
+    L: NtTypedList<T = NtSingleList>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<E, L> Default for NtBoxingSingleListHead<E, L>
+where
+    E: NtBoxedListElement<L = L> + NtListElement<L>,
+    L: NtTypedList<T = NtSingleList>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E, L> Drop for NtBoxingSingleListHead<E, L>
+where
+    E: NtBoxedListElement<L = L> + NtListElement<L>,
+    L: NtTypedList<T = NtSingleList>,
+{
+    fn drop(&mut self) {
+        self.debug_assert_no_cycles();
+
+        for element in self.iter_mut() {
+            // Reconstruct the `Box` we created in push_front and let it leave the scope
+            // to call its Drop handler and deallocate the element gracefully.
+            unsafe {
+                drop(Box::from_raw(element));
+            }
+        }
+    }
+}
+
+/// An element of a singly linked list passed to the closure of
+/// [`NtBoxingSingleListHead::for_each_with_cursor`].
+///
+/// Dropping it without calling [`remove`](Self::remove) keeps the element in the list.
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub struct ElementCursor<
+    'a,
+    E: NtBoxedListElement<L = L> + NtListElement<L>,
+    L: NtTypedList<T = NtSingleList>,
+> {
+    previous: *mut NtSingleListEntry<E, L>,
+    entry: *mut NtSingleListEntry<E, L>,
+    insert_position: *mut NtSingleListEntry<E, L>,
+    phantom: PhantomData<&'a mut NtBoxingSingleListHead<E, L>>,
+}
+
+impl<'a, E, L> ElementCursor<'a, E, L>
+where
+    E: NtBoxedListElement<L = L> + NtListElement<L>,
+    L: NtTypedList<T = NtSingleList>,
+{
+    /// Provides a mutable reference to the element.
+    pub fn element(&mut self) -> &mut E {
+        unsafe { NtSingleListEntry::containing_record_mut(self.entry) }
+    }
+
+    /// Inserts an element after the current one.
+    ///
+    /// Elements inserted via multiple calls keep the order of these calls.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn insert_after(&mut self, element: E) {
+        unsafe {
+            let entry = NtSingleListHead::entry(Box::leak(Box::new(element)));
+            (*entry).next = (*self.insert_position).next;
+            (*self.insert_position).next = entry;
+            self.insert_position = entry;
+        }
+    }
+
+    /// Removes the element from the list and returns it.
+    ///
+    /// Elements inserted before via this [`ElementCursor`] stay in the list.
+    ///
+    /// This operation computes in *O*(*1*) time.
+    pub fn remove(self) -> Box<E> {
+        unsafe {
+            (*self.previous).next = (*self.entry).next;
+            Box::from_raw(NtSingleListEntry::containing_record_mut(self.entry))
+        }
+    }
+}
+
+/// Iterator over the elements of a singly linked list in reverse order.
+///
+/// This iterator is returned from the [`NtBoxingSingleListHead::rev_iter`] function.
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub struct RevIter<'a, E: NtListElement<L>, L: NtTypedList<T = NtSingleList>> {
+    entries: Vec<*const NtSingleListEntry<E, L>>,
+    phantom: PhantomData<&'a NtSingleListHead<E, L>>,
+}
+
+impl<'a, E, L> Iterator for RevIter<'a, E, L>
+where
+    E: NtListElement<L>,
+    L: NtTypedList<T = NtSingleList>,
+{
+    type Item = &'a E;
+
+    fn next(&mut self) -> Option<&'a E> {
+        self.entries
+            .pop()
+            .map(|entry| unsafe { NtSingleListEntry::containing_record(entry) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.entries.len(), Some(self.entries.len()))
+    }
+}
+
+impl<'a, E, L> ExactSizeIterator for RevIter<'a, E, L>
+where
+    E: NtListElement<L>,
+    L: NtTypedList<T = NtSingleList>,
+{
+}
+
+impl<'a, E, L> FusedIterator for RevIter<'a, E, L>
+where
+    E: NtListElement<L>,
+    L: NtTypedList<T = NtSingleList>,
+{
+}
+
+impl<E, L, const N: usize> From<[E; N]> for NtBoxingSingleListHead<E, L>
+where
+    E: NtBoxedListElement<L = L> + NtListElement<L>,
+    L: NtTypedList<T = NtSingleList>,
+{
+    /// Creates a singly linked list from the elements of the given array.
+    ///
+    /// The first array element becomes the first list element.
+    fn from(array: [E; N]) -> Self {
+        array.into_iter().collect()
+    }
+}
+
+impl<E, L> FromIterator<Box<E>> for NtBoxingSingleListHead<E, L>
+where
+    E: NtBoxedListElement<L = L> + NtListElement<L>,
+    L: NtTypedList<T = NtSingleList>,
+{
+    fn from_iter<T>(iter: T) -> Self
+    where
+        T: IntoIterator<Item = Box<E>>,
+    {
+        let mut list = NtBoxingSingleListHead::<E, L>::new();
+        let mut previous =
+            (&mut list.0 as *mut NtSingleListHead<E, L>).cast::<NtSingleListEntry<E, L>>();
+
+        for element in iter.into_iter() {
+            // `NtBoxingSingleListHead` only comes with a `push_front` method, so we have to push
+            // elements by hand and keep track of the last one.
+            unsafe {
+                let entry = NtSingleListHead::entry(Box::leak(element));
+
+                (*entry).next = ptr::null_mut();
+                (*previous).next = entry;
+
+                previous = entry;
+            }
+        }
+
+        list
+    }
+}
+
+impl<E, L> FromIterator<E> for NtBoxingSingleListHead<E, L>
+where
+    E: NtBoxedListElement<L = L> + NtListElement<L>,
+    L: NtTypedList<T = NtSingleList>,
+{
+    fn from_iter<T>(iter: T) -> Self
+    where
+        T: IntoIterator<Item = E>,
+    {
+        iter.into_iter().map(Box::new).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::single_list::NtSingleListEntry;
+
+    #[derive(NtSingleList)]
+    enum MyList {}
+
+    #[derive(Default, NtListElement)]
+    #[repr(C)]
+    struct MyElement {
+        value: i32,
+        #[boxed]
+        entry: NtSingleListEntry<Self, MyList>,
+    }
+
+    impl MyElement {
+        fn new(value: i32) -> Self {
+            Self {
+                value,
+                ..Default::default()
+            }
+        }
+    }
+
+    impl Clone for MyElement {
+        fn clone(&self) -> Self {
+            Self::new(self.value)
+        }
+    }
+
+    impl PartialEq for MyElement {
+        fn eq(&self, other: &Self) -> bool {
+            self.value == other.value
+        }
+    }
+
+    impl Eq for MyElement {}
+
+    impl Ord for MyElement {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.value.cmp(&other.value)
+        }
+    }
+
+    impl PartialOrd for MyElement {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    #[test]
+    fn test_adopt_chain() {
+        // Build a chain by hand, as foreign code would do.
+        let mut first = ptr::null_mut();
+
+        for i in 0..5 {
+            let element = Box::leak(Box::new(MyElement::new(i)));
+            let entry = NtSingleListHead::entry(element);
+            unsafe { (*entry).next = first };
+            first = entry;
+        }
+
+        let list = unsafe { NtBoxingSingleListHead::<MyElement, MyList>::adopt_chain(first) };
+
+        for (i, element) in (0..5).rev().zip(list.iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        let list =
+            unsafe { NtBoxingSingleListHead::<MyElement, MyList>::adopt_chain(ptr::null_mut()) };
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_append_returning_tail() {
+        let mut list = NtBoxingSingleListHead::<MyElement, MyList>::new();
+
+        let tail = list.append_returning_tail(NtBoxingSingleListHead::new());
+        assert!(tail.is_null());
+
+        let tail =
+            list.append_returning_tail(NtBoxingSingleListHead::from([0, 1].map(MyElement::new)));
+        assert_eq!(
+            unsafe { NtSingleListEntry::containing_record(tail) }.value,
+            1
+        );
+
+        // Chain further appends via the returned tail.
+        let mut tail = tail;
+        for values in [[2, 3], [4, 5]] {
+            tail = unsafe {
+                list.append_after_tail(
+                    tail,
+                    NtBoxingSingleListHead::from(values.map(MyElement::new)),
+                )
+            };
+        }
+        let tail = unsafe { list.append_after_tail(tail, NtBoxingSingleListHead::new()) };
+        assert_eq!(
+            unsafe { NtSingleListEntry::containing_record(tail) }.value,
+            5
+        );
+
+        assert_eq!(list.len(), 6);
+
+        for (i, element) in (0..6).zip(list.iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        let tail = list.append_returning_tail(NtBoxingSingleListHead::from([MyElement::new(6)]));
+        assert_eq!(
+            unsafe { NtSingleListEntry::containing_record(tail) }.value,
+            6
+        );
+        assert_eq!(list.len(), 7);
+    }
+
+    #[test]
+    fn test_as_vec() {
+        let mut list =
+            NtBoxingSingleListHead::<MyElement, MyList>::from([0, 1, 2].map(MyElement::new));
+
+        let mut elements = list.as_mut_vec();
+        elements[1].value = 10;
+
+        let elements = list.as_ref_vec();
+        assert_eq!(elements.len(), 3);
+        assert_eq!(elements[0].value, 0);
+        assert_eq!(elements[1].value, 10);
+        assert_eq!(elements[2].value, 2);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "the elements of the list form a cycle")]
+    fn test_cycle() {
+        // Build a chain by hand and let the last element point back to the first one.
+        let mut first = ptr::null_mut();
+        let mut last: *mut NtSingleListEntry<MyElement, MyList> = ptr::null_mut();
+
+        for i in 0..3 {
+            let element = Box::leak(Box::new(MyElement::new(i)));
+            let entry = NtSingleListHead::entry(element);
+            unsafe { (*entry).next = first };
+            first = entry;
+
+            if last.is_null() {
+                last = entry;
+            }
+        }
+
+        unsafe { (*last).next = first };
+
+        let mut list = unsafe { NtBoxingSingleListHead::<MyElement, MyList>::adopt_chain(first) };
+        list.clear();
+    }
+
+    #[test]
+    fn test_clear_after_panic() {
+        extern crate std;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        #[derive(NtListElement)]
+        #[repr(C)]
+        struct PanickingElement {
+            value: i32,
+            #[boxed]
+            entry: NtSingleListEntry<Self, MyList>,
+        }
+
+        impl Drop for PanickingElement {
+            fn drop(&mut self) {
+                if self.value == 2 {
+                    panic!("dropping element 2");
+                }
+            }
+        }
+
+        let mut list = NtBoxingSingleListHead::<PanickingElement, MyList>::new();
+
+        for value in 0..5 {
+            list.push_front(PanickingElement {
+                value,
+                entry: NtSingleListEntry::new(),
+            });
+        }
+
+        let result = catch_unwind(AssertUnwindSafe(|| list.clear()));
+        assert!(result.is_err());
+
+        // The elements after the panicking one must still be in the list.
+        assert_eq!(list.len(), 2);
+        assert!(list.iter().map(|element| element.value).eq([1, 0]));
+
+        // Clearing again drops the remaining elements.
+        list.clear();
+        assert!(list.is_empty());
+
+        // A panic in `f` leaks the element passed to it and leaves the rest in the list.
+        for value in 0..3 {
+            list.push_front(PanickingElement {
+                value: value + 10,
+                entry: NtSingleListEntry::new(),
+            });
+        }
+
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            list.clear_with(|element| {
+                if element.value == 11 {
+                    panic!("clearing element 11");
+                }
+            })
+        }));
+        assert!(result.is_err());
+        assert!(list.iter().map(|element| element.value).eq([10]));
+    }
+
+    #[test]
+    fn test_clear_with() {
+        let mut list = NtBoxingSingleListHead::<MyElement, MyList>::new();
+
+        for i in 0..5 {
+            list.push_front(MyElement::new(i));
+        }
+
+        let mut values = Vec::new();
+        list.clear_with(|element| values.push(element.value));
+
+        assert_eq!(values, [4, 3, 2, 1, 0]);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_cloned() {
+        let list = NtBoxingSingleListHead::<MyElement, MyList>::from([0, 1, 2].map(MyElement::new));
+        let clones = list
+            .cloned()
+            .collect::<NtBoxingSingleListHead<MyElement, MyList>>();
+
+        assert!(list.eq_by(&clones, |a, b| a.value == b.value));
+        assert!(!clones.contains_ptr(list.front().unwrap()));
+    }
+
+    #[test]
+    fn test_collect_into() {
+        let list = NtBoxingSingleListHead::<MyElement, MyList>::from([0, 1, 2].map(MyElement::new));
+
+        let mut buf = Vec::with_capacity(10);
+        buf.push(MyElement::new(-1));
+        list.collect_into(&mut buf);
+        assert!(buf.iter().map(|element| element.value).eq([-1, 0, 1, 2]));
+
+        // The capacity is reused across calls.
+        let capacity = buf.capacity();
+        buf.clear();
+        list.collect_into(&mut buf);
+        assert!(buf.iter().map(|element| element.value).eq([0, 1, 2]));
+        assert_eq!(buf.capacity(), capacity);
+
+        let mut refs = Vec::new();
+        list.collect_refs_into(&mut refs);
+        assert_eq!(refs.len(), 3);
+
+        for (element, list_element) in refs.iter().zip(list.iter()) {
+            assert!(ptr::eq(*element, list_element));
+        }
+    }
+
+    #[test]
+    fn test_concat() {
+        let lists = [
+            NtBoxingSingleListHead::<MyElement, MyList>::from([0, 1, 2].map(MyElement::new)),
+            NtBoxingSingleListHead::new(),
+            NtBoxingSingleListHead::from([3, 4].map(MyElement::new)),
+        ];
+
+        let list = concat(lists);
+        assert_eq!(list.len(), 5);
+
+        for (i, element) in (0..5).zip(list.iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        assert!(
+            concat(core::iter::empty::<NtBoxingSingleListHead<MyElement, MyList>>()).is_empty()
+        );
+    }
+
+    #[test]
+    fn test_contains_ptr() {
+        let list1 = (0..5)
+            .map(MyElement::new)
+            .collect::<NtBoxingSingleListHead<MyElement, MyList>>();
+        let list2 = (0..5)
+            .map(MyElement::new)
+            .collect::<NtBoxingSingleListHead<MyElement, MyList>>();
+
+        assert!(!list1.contains_ptr(ptr::null()));
+
+        for element in list1.iter() {
+            assert!(list1.contains_ptr(element));
+            assert!(!list2.contains_ptr(element));
+        }
+
+        // An equal value is not the same element.
+        let other = MyElement::new(0);
+        assert!(!list1.contains_ptr(&other));
+    }
+
+    #[test]
+    fn test_eq_by() {
+        let list = NtBoxingSingleListHead::<MyElement, MyList>::from([0, 1, 2].map(MyElement::new));
+        let mut other =
+            NtBoxingSingleListHead::<MyElement, MyList>::from([2, 3, 4].map(MyElement::new));
+
+        let same_parity = |a: &MyElement, b: &MyElement| a.value % 2 == b.value % 2;
+        assert!(list.eq_by(&other, same_parity));
+        assert!(!list.eq_by(&other, |a, b| a.value == b.value));
+
+        other.push_front(MyElement::new(1));
+        assert!(!list.eq_by(&other, same_parity));
+        assert!(!other.eq_by(&list, same_parity));
     }
-}
 
-impl<E, L> Drop for NtBoxingSingleListHead<E, L>
-where
-    E: NtBoxedListElement<L = L> + NtListElement<L>,
-    L: NtTypedList<T = NtSingleList>,
-{
-    fn drop(&mut self) {
-        for element in self.iter_mut() {
-            // Reconstruct the `Box` we created in push_front and let it leave the scope
-            // to call its Drop handler and deallocate the element gracefully.
-            unsafe {
-                drop(Box::from_raw(element));
-            }
+    #[test]
+    fn test_extend_count() {
+        let mut list = NtBoxingSingleListHead::<MyElement, MyList>::new();
+        assert_eq!(list.extend_count([0, 1].map(MyElement::new)), 2);
+        assert_eq!(list.extend_count([2, 3, 4].map(MyElement::new)), 3);
+        assert_eq!(list.extend_count(core::iter::empty()), 0);
+
+        assert_eq!(list.len(), 5);
+
+        for (i, element) in (0..5).zip(list.iter()) {
+            assert_eq!(i, element.value);
         }
     }
-}
 
-impl<E, L> FromIterator<Box<E>> for NtBoxingSingleListHead<E, L>
-where
-    E: NtBoxedListElement<L = L> + NtListElement<L>,
-    L: NtTypedList<T = NtSingleList>,
-{
-    fn from_iter<T>(iter: T) -> Self
-    where
-        T: IntoIterator<Item = Box<E>>,
-    {
-        let mut list = NtBoxingSingleListHead::<E, L>::new();
-        let mut previous =
-            (&mut list.0 as *mut NtSingleListHead<E, L>).cast::<NtSingleListEntry<E, L>>();
+    #[test]
+    fn test_find() {
+        let mut list =
+            NtBoxingSingleListHead::<MyElement, MyList>::from([0, 1, 2, 3].map(MyElement::new));
 
-        for element in iter.into_iter() {
-            // `NtBoxingSingleListHead` only comes with a `push_front` method, so we have to push
-            // elements by hand and keep track of the last one.
-            unsafe {
-                let entry = NtSingleListHead::entry(Box::leak(element));
+        assert!(list.find(|element| element.value == 4).is_none());
+        assert_eq!(list.find(|element| element.value > 1).unwrap().value, 2);
 
-                (*entry).next = ptr::null_mut();
-                (*previous).next = entry;
+        list.find_mut(|element| element.value == 3).unwrap().value = 30;
+        assert_eq!(list.iter().nth(3).unwrap().value, 30);
+    }
 
-                previous = entry;
-            }
-        }
+    #[test]
+    fn test_first_duplicate() {
+        let mut list = NtBoxingSingleListHead::<MyElement, MyList>::new();
+        assert!(!list.has_duplicates());
 
-        list
+        list.push_front(MyElement::new(0));
+        list.push_front(MyElement::new(1));
+        assert!(list.first_duplicate().is_none());
+        assert!(!list.has_duplicates());
+
+        list.push_front(MyElement::new(2));
+        list.push_front(MyElement::new(1));
+
+        // The list is now 1, 2, 1, 0, so the second 1 is the first duplicate.
+        let duplicate = list.first_duplicate().unwrap();
+        assert_eq!(duplicate.value, 1);
+        assert!(ptr::eq(duplicate, list.iter().nth(2).unwrap()));
+        assert!(list.has_duplicates());
     }
-}
 
-impl<E, L> FromIterator<E> for NtBoxingSingleListHead<E, L>
-where
-    E: NtBoxedListElement<L = L> + NtListElement<L>,
-    L: NtTypedList<T = NtSingleList>,
-{
-    fn from_iter<T>(iter: T) -> Self
-    where
-        T: IntoIterator<Item = E>,
-    {
-        iter.into_iter().map(Box::new).collect()
+    #[test]
+    fn test_fold() {
+        let list =
+            NtBoxingSingleListHead::<MyElement, MyList>::from([1, 2, 3, 4].map(MyElement::new));
+
+        assert_eq!(list.fold(0, |sum, element| sum + element.value), 10);
+        assert_eq!(
+            list.fold(0, |number, element| number * 10 + element.value),
+            1234
+        );
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::single_list::NtSingleListEntry;
+    #[test]
+    fn test_for_each_with_cursor() {
+        let mut list = NtBoxingSingleListHead::<MyElement, MyList>::from(
+            [0, 1, 2, 3, 4, 5].map(MyElement::new),
+        );
 
-    #[derive(NtSingleList)]
-    enum MyList {}
+        // Inserted elements must not be visited.
+        let mut sum = 0;
+        list.for_each_with_cursor(|mut cursor| {
+            let value = cursor.element().value;
+            sum += value;
 
-    #[derive(Default, NtListElement)]
-    #[repr(C)]
-    struct MyElement {
-        value: i32,
-        #[boxed]
-        entry: NtSingleListEntry<Self, MyList>,
+            match value {
+                1 => {
+                    cursor.insert_after(MyElement::new(10));
+                    cursor.insert_after(MyElement::new(11));
+                }
+                5 => {
+                    cursor.insert_after(MyElement::new(30));
+                    cursor.remove();
+                }
+                _ if value % 3 == 0 => {
+                    cursor.remove();
+                }
+                _ => (),
+            }
+        });
+
+        assert_eq!(sum, 15);
+        assert!(list
+            .iter()
+            .map(|element| element.value)
+            .eq([1, 10, 11, 2, 4, 30]));
     }
 
-    impl MyElement {
-        fn new(value: i32) -> Self {
-            Self {
-                value,
-                ..Default::default()
-            }
+    #[test]
+    fn test_from_array() {
+        let list =
+            NtBoxingSingleListHead::<MyElement, MyList>::from([0, 1, 2, 3].map(MyElement::new));
+
+        assert_eq!(list.len(), 4);
+
+        for (i, element) in (0..4).zip(list.iter()) {
+            assert_eq!(i, element.value);
         }
     }
 
@@ -287,6 +1755,115 @@ mod tests {
         assert_eq!(list.front_mut().unwrap().value, 3);
     }
 
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_index_by() {
+        let list = NtBoxingSingleListHead::<MyElement, MyList>::from(
+            [10, 21, 30, 41, 50].map(MyElement::new),
+        );
+
+        // The last element with a key wins.
+        let index = list.index_by(|element| element.value % 2);
+        assert_eq!(index.len(), 2);
+        assert_eq!(index[&0].value, 50);
+        assert_eq!(index[&1].value, 41);
+
+        let index = list.index_by_multi(|element| element.value % 2);
+        assert_eq!(index.len(), 2);
+        assert!(index[&0]
+            .iter()
+            .map(|element| element.value)
+            .eq([10, 30, 50]));
+        assert!(index[&1].iter().map(|element| element.value).eq([21, 41]));
+    }
+
+    #[test]
+    fn test_iter_enumerated() {
+        let mut list =
+            NtBoxingSingleListHead::<MyElement, MyList>::from([0; 5].map(MyElement::new));
+
+        for (i, element) in list.iter_enumerated_mut() {
+            element.value = i as i32;
+        }
+
+        for (i, element) in list.iter_enumerated() {
+            assert_eq!(i as i32, element.value);
+        }
+    }
+
+    #[test]
+    fn test_iter_step_by() {
+        let mut list = NtBoxingSingleListHead::<MyElement, MyList>::new();
+
+        for i in 0..10 {
+            list.push_front(MyElement::new(i));
+        }
+
+        let values = list
+            .iter_step_by(4)
+            .map(|element| element.value)
+            .collect::<Vec<_>>();
+        assert_eq!(values, [9, 5, 1]);
+    }
+
+    #[test]
+    fn test_map_to_vec() {
+        let mut list =
+            NtBoxingSingleListHead::<MyElement, MyList>::from([0, 1, 2, 3].map(MyElement::new));
+
+        let doubled = list.map_to_vec(|element| element.value * 2);
+        assert_eq!(doubled, [0, 2, 4, 6]);
+
+        let taken = list.map_to_vec_mut(|element| mem::replace(&mut element.value, -1));
+        assert_eq!(taken, [0, 1, 2, 3]);
+        assert!(list.iter().all(|element| element.value == -1));
+    }
+
+    #[test]
+    fn test_merge_by() {
+        let mut list =
+            NtBoxingSingleListHead::<MyElement, MyList>::from([1, 3, 5, 7, 9].map(MyElement::new));
+        let other = NtBoxingSingleListHead::from([0, 3, 4, 10, 11].map(MyElement::new));
+        let other_three = other.iter().nth(1).unwrap() as *const MyElement;
+
+        list.merge_by(other, |a, b| a.value.cmp(&b.value));
+
+        assert_eq!(list.len(), 10);
+
+        for (i, element) in [0, 1, 3, 3, 4, 5, 7, 9, 10, 11]
+            .into_iter()
+            .zip(list.iter())
+        {
+            assert_eq!(i, element.value);
+        }
+
+        // The 3 from `other` must come after the 3 from `list`.
+        assert_eq!(list.iter().nth(3).unwrap() as *const MyElement, other_three);
+
+        // Merging with and into empty lists.
+        list.merge_by(NtBoxingSingleListHead::new(), |a, b| a.value.cmp(&b.value));
+        assert_eq!(list.len(), 10);
+
+        let mut empty = NtBoxingSingleListHead::<MyElement, MyList>::new();
+        empty.merge_by(list, |a, b| a.value.cmp(&b.value));
+        assert_eq!(empty.len(), 10);
+    }
+
+    #[test]
+    fn test_partition() {
+        let list = NtBoxingSingleListHead::<MyElement, MyList>::from(
+            [0, 1, 2, 3, 4, 5, 6].map(MyElement::new),
+        );
+
+        let (even, odd) = list.partition(|element| element.value % 2 == 0);
+        assert!(even.iter().map(|element| element.value).eq([0, 2, 4, 6]));
+        assert!(odd.iter().map(|element| element.value).eq([1, 3, 5]));
+
+        let (all, none) = even.partition(|_| true);
+        assert_eq!(all.len(), 4);
+        assert!(none.is_empty());
+    }
+
     #[test]
     fn test_pop_front() {
         let mut list = NtBoxingSingleListHead::<MyElement, MyList>::new();
@@ -303,6 +1880,67 @@ mod tests {
         assert!(list.is_empty());
     }
 
+    #[test]
+    fn test_pop_front_if() {
+        let mut list =
+            NtBoxingSingleListHead::<MyElement, MyList>::from([0, 1, 2, 3].map(MyElement::new));
+
+        assert_eq!(
+            list.pop_front_if(|element| element.value == 0)
+                .unwrap()
+                .value,
+            0
+        );
+        assert!(list.pop_front_if(|element| element.value == 0).is_none());
+        assert_eq!(list.front().unwrap().value, 1);
+        assert_eq!(list.len(), 3);
+
+        list.clear();
+        assert!(list.pop_front_if(|_| true).is_none());
+    }
+
+    #[test]
+    fn test_pop_n_front() {
+        let mut list =
+            NtBoxingSingleListHead::<MyElement, MyList>::from([0, 1, 2, 3, 4].map(MyElement::new));
+
+        assert!(list.pop_n_front(0).is_empty());
+
+        let popped = list.pop_n_front(2);
+        assert_eq!(popped.len(), 2);
+        assert_eq!(list.len(), 3);
+
+        for (i, element) in (0..2).zip(popped.iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        for (i, element) in (2..5).zip(list.iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        let popped = list.pop_n_front(10);
+        assert_eq!(popped.len(), 3);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_prepend_iter() {
+        let mut list = NtBoxingSingleListHead::<MyElement, MyList>::new();
+
+        list.prepend_iter(core::iter::empty());
+        assert!(list.is_empty());
+
+        list.prepend_iter([3, 4].map(MyElement::new));
+        list.prepend_iter([0, 1, 2].map(MyElement::new));
+        list.prepend_iter(core::iter::empty());
+
+        assert_eq!(list.len(), 5);
+
+        for (i, element) in (0..5).zip(list.iter()) {
+            assert_eq!(i, element.value);
+        }
+    }
+
     #[test]
     fn test_push_front() {
         let mut list = NtBoxingSingleListHead::<MyElement, MyList>::new();
@@ -343,4 +1981,166 @@ mod tests {
         assert_eq!(iter.next().unwrap().value, 0);
         assert!(matches!(iter.next(), None));
     }
+
+    #[test]
+    fn test_retain_until() {
+        let mut list = NtBoxingSingleListHead::<MyElement, MyList>::from(
+            [0, 1, 2, 3, 4, 5, 6].map(MyElement::new),
+        );
+        let mut visited = 0;
+
+        // Remove the odd elements below 4 and keep everything from 4 onwards.
+        list.retain_until(|element| {
+            visited += 1;
+
+            if element.value >= 4 {
+                Decision::StopKeepingRest
+            } else if element.value % 2 == 1 {
+                Decision::Remove
+            } else {
+                Decision::Keep
+            }
+        });
+
+        assert_eq!(visited, 5);
+        assert!(list.iter().map(|element| element.value).eq([0, 2, 4, 5, 6]));
+
+        list.retain_until(|_| Decision::Remove);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_rev_iter() {
+        let list = (0..10)
+            .map(MyElement::new)
+            .collect::<NtBoxingSingleListHead<MyElement, MyList>>();
+
+        let iter = list.rev_iter();
+        assert_eq!(iter.len(), 10);
+
+        for (i, element) in (0..10).rev().zip(iter) {
+            assert_eq!(i, element.value);
+        }
+
+        let list = NtBoxingSingleListHead::<MyElement, MyList>::new();
+        assert!(list.rev_iter().next().is_none());
+    }
+
+    #[test]
+    fn test_rfind_and_rposition() {
+        let list =
+            NtBoxingSingleListHead::<MyElement, MyList>::from([0, 3, 1, 3, 2].map(MyElement::new));
+
+        let element = list.rfind(|element| element.value == 3).unwrap();
+        assert!(ptr::eq(element, list.iter().nth(3).unwrap()));
+        assert_eq!(list.rposition(|element| element.value == 3), Some(3));
+
+        assert_eq!(list.rfind(|element| element.value < 2).unwrap().value, 1);
+        assert_eq!(list.rposition(|element| element.value < 2), Some(2));
+
+        assert!(list.rfind(|element| element.value > 3).is_none());
+        assert_eq!(list.rposition(|element| element.value > 3), None);
+    }
+
+    #[test]
+    fn test_rotate() {
+        let mut list = NtBoxingSingleListHead::<MyElement, MyList>::new();
+        list.rotate_left(3);
+        list.rotate_right(3);
+        assert!(list.is_empty());
+
+        list = NtBoxingSingleListHead::from([0, 1, 2, 3, 4].map(MyElement::new));
+
+        list.rotate_left(2);
+        for (i, element) in [2, 3, 4, 0, 1].into_iter().zip(list.iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        list.rotate_right(2);
+        for (i, element) in (0..5).zip(list.iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        // `n` is taken modulo the length.
+        list.rotate_left(5);
+        list.rotate_right(11);
+        for (i, element) in [4, 0, 1, 2, 3].into_iter().zip(list.iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        assert_eq!(list.len(), 5);
+    }
+
+    #[test]
+    fn test_split_first() {
+        let mut list = NtBoxingSingleListHead::<MyElement, MyList>::new();
+        assert!(list.split_first().is_none());
+
+        list = NtBoxingSingleListHead::from([0, 1, 2].map(MyElement::new));
+
+        let (first, rest) = list.split_first().unwrap();
+        assert_eq!(first.value, 0);
+        assert!(list.is_empty());
+        assert_eq!(rest.len(), 2);
+
+        for (i, element) in (1..3).zip(rest.iter()) {
+            assert_eq!(i, element.value);
+        }
+    }
+
+    #[test]
+    fn test_swap() {
+        let mut list =
+            NtBoxingSingleListHead::<MyElement, MyList>::from([0, 1, 2].map(MyElement::new));
+        let mut other =
+            NtBoxingSingleListHead::<MyElement, MyList>::from([3, 4].map(MyElement::new));
+
+        list.swap(&mut other);
+
+        assert_eq!(list.len(), 2);
+        assert_eq!(other.len(), 3);
+
+        for (i, element) in (3..5).zip(list.iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        for (i, element) in (0..3).zip(other.iter()) {
+            assert_eq!(i, element.value);
+        }
+
+        // Swapping with an empty list.
+        let mut empty = NtBoxingSingleListHead::<MyElement, MyList>::new();
+        list.swap(&mut empty);
+        assert!(list.is_empty());
+        assert_eq!(empty.len(), 2);
+    }
+
+    #[test]
+    fn test_to_btree_set() {
+        let list =
+            NtBoxingSingleListHead::<MyElement, MyList>::from([3, 1, 3, 2, 1].map(MyElement::new));
+
+        // Duplicates are dropped and the set is ordered by value, not by list position.
+        let set = list.to_btree_set();
+        assert_eq!(set.len(), 3);
+        assert!(set.iter().map(|element| element.value).eq([1, 2, 3]));
+
+        // The list itself is unchanged.
+        assert!(list.iter().map(|element| element.value).eq([3, 1, 3, 2, 1]));
+    }
+
+    #[test]
+    fn test_try_push_front() {
+        let mut list = NtBoxingSingleListHead::<MyElement, MyList>::new();
+
+        for i in 0..10 {
+            assert!(list.try_push_front(MyElement::new(i)).is_ok());
+        }
+
+        assert_eq!(list.len(), 10);
+
+        for (i, element) in (0..10).rev().zip(list.iter()) {
+            assert_eq!(i, element.value);
+        }
+    }
 }
@@ -72,13 +72,21 @@
 //! This is why almost all [`NtSingleListHead`] functions are `unsafe`.
 //! Fortunately, [`NtSingleListHead`] is usually only necessary when an element is part of multiple lists.
 //!
+//! With the `bumpalo` feature enabled, `ArenaNtBoxingSingleListHead` offers an alternative to
+//! [`NtBoxingSingleListHead`] that allocates all elements in a [`bumpalo`] arena.
+//!
+//! [`bumpalo`]: https://crates.io/crates/bumpalo
 //! [`SINGLE_LIST_ENTRY`]: https://docs.microsoft.com/en-us/windows/win32/api/ntdef/ns-ntdef-single_list_entry
 
+#[cfg(feature = "bumpalo")]
+mod arena;
 mod base;
 #[cfg(feature = "alloc")]
 mod boxing;
 mod traits;
 
+#[cfg(feature = "bumpalo")]
+pub use arena::*;
 pub use base::*;
 #[cfg(feature = "alloc")]
 pub use boxing::*;
@@ -1,8 +1,11 @@
 // Copyright 2022 Colin Finck <colin@reactos.org>
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
+use core::alloc::Layout;
+
+use super::base::NtSingleListEntry;
 use crate::private::Sealed;
-use crate::traits::NtListType;
+use crate::traits::{NtListElement, NtListType, NtTypedList};
 
 /// Designates a list as an NT singly linked list (`SINGLE_LIST_ENTRY` structure of the Windows NT API).
 ///
@@ -13,7 +16,15 @@ pub enum NtSingleList {}
 
 /// Singly linked list type (`SINGLE_LIST_ENTRY` structure of the Windows NT API)
 impl NtListType for NtSingleList {}
-impl Sealed for NtSingleList {}
+impl Sealed for NtSingleList {
+    fn entry_layout<E, L>() -> Layout
+    where
+        E: NtListElement<L>,
+        L: NtTypedList<T = Self>,
+    {
+        Layout::new::<NtSingleListEntry<E, L>>()
+    }
+}
 
 /// Designates an empty enum as a singly linked list.
 ///
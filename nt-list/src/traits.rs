@@ -66,13 +66,15 @@ pub trait NtTypedList {
 /// implemented correctly.
 /// Safe functions rely on the offset pointing to an actual [`NtListEntry`] or [`NtSingleListEntry`].
 /// This trait must also only be implemented for structures marked with `#[repr(C)]`.
+/// These structures must be at least as strictly aligned as their entry fields, which the derive
+/// checks at compile time.
 ///
 /// It is therefore recommended to only derive this trait as described above and never implement
 /// it manually.
 ///
 /// [`NtListEntry`]: crate::list::NtListEntry
 /// [`NtSingleListEntry`]: crate::single_list::NtSingleListEntry
-pub unsafe trait NtListElement<L: NtTypedList> {
+pub unsafe trait NtListElement<L: NtTypedList>: Sized {
     /// Returns the byte offset to the entry field relative to the beginning of the
     /// element structure.
     fn offset() -> usize;
@@ -74,6 +74,13 @@ pub fn derive_list_struct_trait(input: DeriveInput) -> Result<TokenStream> {
             let list_ty = info.list_ty;
             boxed_attrs += info.is_boxed as usize;
 
+            // The field type may refer to `Self`, which is unavailable outside the impl block.
+            let entry_ty = if info.entry_ident == "NtListEntry" {
+                quote! { ::nt_list::list::NtListEntry<#ident, #list_ty> }
+            } else {
+                quote! { ::nt_list::single_list::NtSingleListEntry<#ident, #list_ty> }
+            };
+
             let mut boxed_impl = TokenStream::new();
             if info.is_boxed {
                 boxed_impl = quote! {
@@ -93,6 +100,13 @@ pub fn derive_list_struct_trait(input: DeriveInput) -> Result<TokenStream> {
                     }
                 }
 
+                // Element references are formed from entry pointers, so the element must be at
+                // least as strictly aligned as its entry.
+                // This fails to compile with a mismatched array length if that's not the case.
+                // `assert!` in constants would require Rust 1.57.
+                const _: [(); 0] = [(); (::core::mem::align_of::<#ident>()
+                    < ::core::mem::align_of::<#entry_ty>()) as usize];
+
                 #boxed_impl
             }
         })
@@ -142,6 +156,8 @@ fn has_repr_c(input: &DeriveInput) -> bool {
 pub(crate) struct ElementFieldInfo<'a> {
     /// The "entry" in `entry: nt_list::list::base::NtListEntry<Self, mytraits::MyList>`
     pub(crate) ident: &'a Ident,
+    /// The "NtListEntry" in `entry: nt_list::list::base::NtListEntry<Self, mytraits::MyList>`
+    pub(crate) entry_ident: &'a Ident,
     /// The "mytraits::MyList" in `entry: nt_list::list::base::NtListEntry<Self, mytraits::MyList>`
     pub(crate) list_ty: &'a TypePath,
     /// Whether a `#[boxed]` attribute has been placed before the field.
@@ -196,6 +212,7 @@ pub(crate) fn parse_element_field(field: &Field) -> Option<ElementFieldInfo> {
 
     Some(ElementFieldInfo {
         ident,
+        entry_ident: &segment.ident,
         list_ty,
         is_boxed,
     })